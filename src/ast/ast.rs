@@ -1,21 +1,39 @@
 use core::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::lexer::token::Token;
+#[cfg(feature = "serde")]
+use crate::lexer::token::borrow_str;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// `span` on a node is the byte range of source it was parsed from, or `None`
+/// for nodes built outside the parser, such as by `unquote`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 's")))]
 pub enum Statement<'s> {
     Let {
         token: Token<'s>,
         name: Identifier<'s>,
         value: Expression<'s>,
+        span: Option<(usize, usize)>,
+    },
+    Assign {
+        token: Token<'s>,
+        name: Identifier<'s>,
+        value: Expression<'s>,
+        span: Option<(usize, usize)>,
     },
     Return {
         token: Token<'s>,
         value: Expression<'s>,
+        span: Option<(usize, usize)>,
     },
     Block {
         token: Token<'s>,
         statements: Vec<Statement<'s>>,
+        span: Option<(usize, usize)>,
     },
     Expression(Expression<'s>),
 }
@@ -23,12 +41,17 @@ pub enum Statement<'s> {
 impl fmt::Display for Statement<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Statement::Let { token, name, value } => {
+            Statement::Let {
+                token, name, value, ..
+            } => {
                 write!(f, "{} {} = {};", token.literal, name.token.literal, value)
             }
-            Statement::Return { value, .. } => {
-                write!(f, "return {};", value.token_literal())
-            }
+            Statement::Assign { name, value, .. } => write!(f, "{} = {};", name, value),
+            Statement::Return {
+                value: Expression::None,
+                ..
+            } => write!(f, "return;"),
+            Statement::Return { value, .. } => write!(f, "return {};", value),
             Statement::Expression(expression) => write!(f, "{}", expression),
             Statement::Block { statements, .. } => {
                 for stmt in statements {
@@ -44,6 +67,7 @@ impl Statement<'_> {
     pub fn token_literal(&self) -> String {
         match self {
             Statement::Let { token, .. } => token.literal.to_string(),
+            Statement::Assign { token, .. } => token.literal.to_string(),
             Statement::Return { token, .. } => token.literal.to_string(),
             Statement::Expression(expression) => expression.token_literal().to_string(),
             Statement::Block { token, .. } => token.literal.to_string(),
@@ -51,42 +75,96 @@ impl Statement<'_> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'e")))]
 pub enum Expression<'e> {
     Integer {
         token: Token<'e>,
         value: i64,
+        span: Option<(usize, usize)>,
+    },
+    Float {
+        token: Token<'e>,
+        value: f64,
+        span: Option<(usize, usize)>,
     },
     Boolean {
         token: Token<'e>,
         value: bool,
+        span: Option<(usize, usize)>,
+    },
+    StringLiteral {
+        token: Token<'e>,
+        value: String,
+        span: Option<(usize, usize)>,
+    },
+    NullLiteral {
+        token: Token<'e>,
+        span: Option<(usize, usize)>,
     },
     Prefix {
         token: Token<'e>,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "borrow_str"))]
         operator: &'e str,
         right: Box<Option<Expression<'e>>>,
+        span: Option<(usize, usize)>,
     },
     Infix {
         token: Token<'e>,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "borrow_str"))]
         operator: &'e str,
         right: Box<Expression<'e>>,
         left: Box<Expression<'e>>,
+        span: Option<(usize, usize)>,
     },
     If {
         token: Token<'e>,
         condition: Box<Expression<'e>>,
         consequence: Box<Statement<'e>>,
         alternative: Option<Box<Statement<'e>>>,
+        span: Option<(usize, usize)>,
+    },
+    Ternary {
+        token: Token<'e>,
+        condition: Box<Expression<'e>>,
+        then: Box<Expression<'e>>,
+        otherwise: Box<Expression<'e>>,
+        span: Option<(usize, usize)>,
     },
     Function {
         token: Token<'e>,
         parameters: Vec<Identifier<'e>>,
         body: Box<Statement<'e>>,
+        span: Option<(usize, usize)>,
+    },
+    MacroLiteral {
+        token: Token<'e>,
+        parameters: Vec<Identifier<'e>>,
+        body: Box<Statement<'e>>,
+        span: Option<(usize, usize)>,
     },
     Call {
         token: Token<'e>,
         function: Box<Expression<'e>>,
-        arguments: Option<Vec<Expression<'e>>>,
+        arguments: Vec<Expression<'e>>,
+        span: Option<(usize, usize)>,
+    },
+    ArrayLiteral {
+        token: Token<'e>,
+        elements: Vec<Expression<'e>>,
+        span: Option<(usize, usize)>,
+    },
+    HashLiteral {
+        token: Token<'e>,
+        pairs: Vec<(Expression<'e>, Expression<'e>)>,
+        span: Option<(usize, usize)>,
+    },
+    Index {
+        token: Token<'e>,
+        left: Box<Expression<'e>>,
+        index: Box<Expression<'e>>,
+        span: Option<(usize, usize)>,
     },
     Identifier(Identifier<'e>),
     None,
@@ -95,7 +173,9 @@ pub enum Expression<'e> {
 impl Expression<'_> {
     pub fn token_literal(&self) -> String {
         match self {
-            Expression::Integer { token, .. } => token.literal.to_owned(),
+            Expression::Integer { token, .. } => token.literal.to_string(),
+            Expression::Float { token, .. } => token.literal.to_string(),
+            Expression::NullLiteral { token, .. } => token.literal.to_string(),
             Expression::Prefix {
                 operator, right, ..
             } => match right.as_ref() {
@@ -124,6 +204,12 @@ impl Expression<'_> {
                 Some(x) => format!("if {} {{ {} }} else {{ {} }}", condition, consequence, x,),
                 None => format!("if {} {{ {} }}", condition, consequence,),
             },
+            Expression::Ternary {
+                condition,
+                then,
+                otherwise,
+                ..
+            } => format!("({} ? {} : {})", condition, then, otherwise),
             Expression::Function {
                 parameters, body, ..
             } => format!(
@@ -136,25 +222,49 @@ impl Expression<'_> {
                     .join(" , "),
                 body
             ),
+            Expression::MacroLiteral {
+                parameters, body, ..
+            } => format!(
+                "macro({}) {{ {} }}",
+                parameters
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                body
+            ),
             Expression::Call {
                 function,
                 arguments,
                 ..
-            } => {
-                let mut args: String = String::new();
-
-                if let Some(argument) = arguments {
-                    let args_formatted = argument
-                        .iter()
-                        .map(|f| f.to_string())
-                        .collect::<Vec<_>>()
-                        .join(", ");
-
-                    args.push_str(&args_formatted);
-                }
-                format!("{}({})", function.token_literal(), args)
-            }
+            } => format!(
+                "{}({})",
+                function.token_literal(),
+                arguments
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
             Expression::Boolean { value, .. } => value.to_string(),
+            Expression::HashLiteral { pairs, .. } => format!(
+                "{{{}}}",
+                pairs
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Expression::Index { left, index, .. } => format!("({}[{}])", left, index),
+            Expression::StringLiteral { value, .. } => value.to_string(),
+            Expression::ArrayLiteral { elements, .. } => format!(
+                "[{}]",
+                elements
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -165,7 +275,9 @@ impl fmt::Display for Expression<'_> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'p")))]
 pub struct Program<'p> {
     pub statements: Vec<Statement<'p>>,
 }
@@ -178,16 +290,33 @@ impl Program<'_> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'p> Program<'p> {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Identifiers and operators borrow from `json`, so they must appear without
+    /// escapes, as `to_json` writes them. Literal tokens may be escaped.
+    pub fn from_json(json: &'p str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
 impl Default for Program<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'i")))]
 pub struct Identifier<'i> {
     pub token: Token<'i>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "borrow_str"))]
     pub value: &'i str,
+    pub span: Option<(usize, usize)>,
 }
 
 impl fmt::Display for Identifier<'_> {
@@ -198,6 +327,6 @@ impl fmt::Display for Identifier<'_> {
 
 impl<'i> Identifier<'i> {
     pub fn token_literal(&self) -> &'i str {
-        self.token.literal
+        self.value
     }
 }
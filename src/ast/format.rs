@@ -0,0 +1,219 @@
+use crate::{lexer::precedence::Precedence, parser::parser::Parser};
+
+use super::ast::{Expression, Program, Statement};
+
+const INDENT: &str = "    ";
+
+impl Program<'_> {
+    /// Renders the program back into canonically formatted Monkey source.
+    ///
+    /// Block bodies go on their own lines, indented by four spaces, and
+    /// parentheses are only emitted where precedence requires them.
+    pub fn format(&self) -> String {
+        let mut out = String::new();
+        for statement in &self.statements {
+            format_statement(statement, 0, &mut out);
+        }
+        out
+    }
+}
+
+fn format_statement(statement: &Statement, depth: usize, out: &mut String) {
+    out.push_str(&INDENT.repeat(depth));
+    match statement {
+        Statement::Let { name, value, .. } => {
+            out.push_str(&format!(
+                "let {} = {};",
+                name,
+                format_expression(value, depth)
+            ));
+        }
+        Statement::Assign { name, value, .. } => {
+            out.push_str(&format!("{} = {};", name, format_expression(value, depth)));
+        }
+        Statement::Return { value, .. } => match value {
+            Expression::None => out.push_str("return;"),
+            value => out.push_str(&format!("return {};", format_expression(value, depth))),
+        },
+        Statement::Block { .. } => out.push_str(&format_block(statement, depth)),
+        Statement::Expression(expression) => {
+            out.push_str(&format_expression(expression, depth));
+            if !matches!(expression, Expression::If { .. }) {
+                out.push(';');
+            }
+        }
+    }
+    out.push('\n');
+}
+
+fn format_block(block: &Statement, depth: usize) -> String {
+    let statements = match block {
+        Statement::Block { statements, .. } => statements,
+        other => std::slice::from_ref(other),
+    };
+    if statements.is_empty() {
+        return String::from("{}");
+    }
+
+    let mut out = String::from("{\n");
+    for statement in statements {
+        format_statement(statement, depth + 1, &mut out);
+    }
+    out.push_str(&INDENT.repeat(depth));
+    out.push('}');
+    out
+}
+
+fn format_list(expressions: &[Expression], depth: usize) -> String {
+    expressions
+        .iter()
+        .map(|expression| format_expression(expression, depth))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn precedence(expression: &Expression) -> Precedence {
+    match expression {
+        Expression::Infix { token, .. } => Parser::get_precedence_of_token(token.token_type),
+        Expression::Prefix { .. } => Precedence::Prefix,
+        Expression::Ternary { .. } => Precedence::Ternary,
+        _ => Precedence::Index,
+    }
+}
+
+fn format_operand(expression: &Expression, depth: usize, needs_parens: bool) -> String {
+    let formatted = format_expression(expression, depth);
+    if needs_parens {
+        format!("({})", formatted)
+    } else {
+        formatted
+    }
+}
+
+fn format_expression(expression: &Expression, depth: usize) -> String {
+    match expression {
+        Expression::Integer { token, .. }
+        | Expression::Float { token, .. }
+        | Expression::Boolean { token, .. }
+        | Expression::NullLiteral { token, .. } => token.literal.to_string(),
+        Expression::StringLiteral { token, .. } => format!("\"{}\"", token.literal),
+        Expression::Identifier(identifier) => identifier.value.to_string(),
+        Expression::Prefix {
+            operator, right, ..
+        } => match right.as_ref() {
+            Some(right) => format!(
+                "{}{}",
+                operator,
+                format_operand(right, depth, precedence(right) < Precedence::Prefix)
+            ),
+            None => operator.to_string(),
+        },
+        Expression::Infix {
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            let own = precedence(expression);
+            let (left_parens, right_parens) = match expression {
+                Expression::Infix { token, .. }
+                    if Precedence::is_right_associative(token.token_type) =>
+                {
+                    (precedence(left) <= own, precedence(right) < own)
+                }
+                _ => (precedence(left) < own, precedence(right) <= own),
+            };
+            format!(
+                "{} {} {}",
+                format_operand(left, depth, left_parens),
+                operator,
+                format_operand(right, depth, right_parens)
+            )
+        }
+        Expression::If {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            let mut out = format!(
+                "if ({}) {}",
+                format_expression(condition, depth),
+                format_block(consequence, depth)
+            );
+            if let Some(alternative) = alternative {
+                out.push_str(&format!(" else {}", format_block(alternative, depth)));
+            }
+            out
+        }
+        // The condition is parenthesized if it is itself a ternary; the branches
+        // never need it, as the ternary groups to the right
+        Expression::Ternary {
+            condition,
+            then,
+            otherwise,
+            ..
+        } => format!(
+            "{} ? {} : {}",
+            format_operand(
+                condition,
+                depth,
+                precedence(condition) <= Precedence::Ternary
+            ),
+            format_expression(then, depth),
+            format_expression(otherwise, depth)
+        ),
+        Expression::Function {
+            parameters, body, ..
+        } => format!(
+            "fn({}) {}",
+            parameters
+                .iter()
+                .map(|parameter| parameter.value)
+                .collect::<Vec<_>>()
+                .join(", "),
+            format_block(body, depth)
+        ),
+        Expression::MacroLiteral {
+            parameters, body, ..
+        } => format!(
+            "macro({}) {}",
+            parameters
+                .iter()
+                .map(|parameter| parameter.value)
+                .collect::<Vec<_>>()
+                .join(", "),
+            format_block(body, depth)
+        ),
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => format!(
+            "{}({})",
+            format_operand(function, depth, precedence(function) < Precedence::Call),
+            format_list(arguments, depth)
+        ),
+        Expression::ArrayLiteral { elements, .. } => {
+            format!("[{}]", format_list(elements, depth))
+        }
+        Expression::HashLiteral { pairs, .. } => format!(
+            "{{{}}}",
+            pairs
+                .iter()
+                .map(|(key, value)| format!(
+                    "{}: {}",
+                    format_expression(key, depth),
+                    format_expression(value, depth)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Expression::Index { left, index, .. } => format!(
+            "{}[{}]",
+            format_operand(left, depth, precedence(left) < Precedence::Index),
+            format_expression(index, depth)
+        ),
+        Expression::None => String::new(),
+    }
+}
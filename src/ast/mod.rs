@@ -1,2 +1,8 @@
 pub mod ast;
+mod format;
+pub mod modify;
+pub mod span;
+pub mod visitor;
 pub use ast::{Expression, Identifier, Program, Statement};
+pub use span::Node;
+pub use visitor::{Visitor, walk_program};
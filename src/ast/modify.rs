@@ -0,0 +1,227 @@
+use super::ast::{Expression, Program, Statement};
+
+/// Rebuilds the tree bottom-up, passing every expression through `modifier`
+/// after its children have been modified.
+pub fn modify_program<'a>(
+    program: Program<'a>,
+    modifier: &mut dyn FnMut(Expression<'a>) -> Expression<'a>,
+) -> Program<'a> {
+    Program {
+        statements: program
+            .statements
+            .into_iter()
+            .map(|statement| modify_statement(statement, modifier))
+            .collect(),
+    }
+}
+
+pub fn modify_statement<'a>(
+    statement: Statement<'a>,
+    modifier: &mut dyn FnMut(Expression<'a>) -> Expression<'a>,
+) -> Statement<'a> {
+    crate::grow_stack(|| modify_statement_on_stack(statement, modifier))
+}
+
+fn modify_statement_on_stack<'a>(
+    statement: Statement<'a>,
+    modifier: &mut dyn FnMut(Expression<'a>) -> Expression<'a>,
+) -> Statement<'a> {
+    match statement {
+        Statement::Let {
+            token,
+            name,
+            value,
+            span,
+        } => Statement::Let {
+            token,
+            name,
+            span,
+            value: modify_expression(value, modifier),
+        },
+        Statement::Assign {
+            token,
+            name,
+            value,
+            span,
+        } => Statement::Assign {
+            token,
+            name,
+            span,
+            value: modify_expression(value, modifier),
+        },
+        Statement::Return { token, value, span } => Statement::Return {
+            token,
+            span,
+            value: modify_expression(value, modifier),
+        },
+        Statement::Block {
+            token,
+            statements,
+            span,
+        } => Statement::Block {
+            token,
+            span,
+            statements: statements
+                .into_iter()
+                .map(|statement| modify_statement(statement, modifier))
+                .collect(),
+        },
+        Statement::Expression(expression) => {
+            Statement::Expression(modify_expression(expression, modifier))
+        }
+    }
+}
+
+pub fn modify_expression<'a>(
+    expression: Expression<'a>,
+    modifier: &mut dyn FnMut(Expression<'a>) -> Expression<'a>,
+) -> Expression<'a> {
+    crate::grow_stack(|| modify_expression_on_stack(expression, modifier))
+}
+
+fn modify_expression_on_stack<'a>(
+    expression: Expression<'a>,
+    modifier: &mut dyn FnMut(Expression<'a>) -> Expression<'a>,
+) -> Expression<'a> {
+    let modified = match expression {
+        Expression::Prefix {
+            token,
+            operator,
+            right,
+            span,
+        } => Expression::Prefix {
+            token,
+            operator,
+            span,
+            right: Box::new(right.map(|right| modify_expression(right, modifier))),
+        },
+        Expression::Infix {
+            token,
+            operator,
+            right,
+            left,
+            span,
+        } => {
+            let left = Box::new(modify_expression(*left, modifier));
+            let right = Box::new(modify_expression(*right, modifier));
+            Expression::Infix {
+                token,
+                operator,
+                right,
+                left,
+                span,
+            }
+        }
+        Expression::If {
+            token,
+            condition,
+            consequence,
+            alternative,
+            span,
+        } => Expression::If {
+            token,
+            span,
+            condition: Box::new(modify_expression(*condition, modifier)),
+            consequence: Box::new(modify_statement(*consequence, modifier)),
+            alternative: alternative
+                .map(|alternative| Box::new(modify_statement(*alternative, modifier))),
+        },
+        Expression::Ternary {
+            token,
+            condition,
+            then,
+            otherwise,
+            span,
+        } => {
+            let condition = Box::new(modify_expression(*condition, modifier));
+            let then = Box::new(modify_expression(*then, modifier));
+            Expression::Ternary {
+                token,
+                condition,
+                then,
+                otherwise: Box::new(modify_expression(*otherwise, modifier)),
+                span,
+            }
+        }
+        Expression::Function {
+            token,
+            parameters,
+            body,
+            span,
+        } => Expression::Function {
+            token,
+            parameters,
+            span,
+            body: Box::new(modify_statement(*body, modifier)),
+        },
+        Expression::MacroLiteral {
+            token,
+            parameters,
+            body,
+            span,
+        } => Expression::MacroLiteral {
+            token,
+            parameters,
+            span,
+            body: Box::new(modify_statement(*body, modifier)),
+        },
+        Expression::Call {
+            token,
+            function,
+            arguments,
+            span,
+        } => Expression::Call {
+            token,
+            span,
+            function: Box::new(modify_expression(*function, modifier)),
+            arguments: modify_expressions(arguments, modifier),
+        },
+        Expression::ArrayLiteral {
+            token,
+            elements,
+            span,
+        } => Expression::ArrayLiteral {
+            token,
+            span,
+            elements: modify_expressions(elements, modifier),
+        },
+        Expression::HashLiteral { token, pairs, span } => Expression::HashLiteral {
+            token,
+            span,
+            pairs: pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = modify_expression(key, modifier);
+                    (key, modify_expression(value, modifier))
+                })
+                .collect(),
+        },
+        Expression::Index {
+            token,
+            left,
+            index,
+            span,
+        } => {
+            let left = Box::new(modify_expression(*left, modifier));
+            Expression::Index {
+                token,
+                left,
+                span,
+                index: Box::new(modify_expression(*index, modifier)),
+            }
+        }
+        leaf => leaf,
+    };
+
+    modifier(modified)
+}
+
+fn modify_expressions<'a>(
+    expressions: Vec<Expression<'a>>,
+    modifier: &mut dyn FnMut(Expression<'a>) -> Expression<'a>,
+) -> Vec<Expression<'a>> {
+    expressions
+        .into_iter()
+        .map(|expression| modify_expression(expression, modifier))
+        .collect()
+}
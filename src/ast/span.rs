@@ -0,0 +1,167 @@
+use super::ast::{Expression, Identifier, Program, Statement};
+
+/// A borrowed AST node, as returned by [`Program::node_at`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Node<'n, 'a> {
+    Statement(&'n Statement<'a>),
+    Expression(&'n Expression<'a>),
+    Identifier(&'n Identifier<'a>),
+}
+
+impl Node<'_, '_> {
+    pub fn span(&self) -> Option<(usize, usize)> {
+        match self {
+            Node::Statement(statement) => statement.span(),
+            Node::Expression(expression) => expression.span(),
+            Node::Identifier(identifier) => identifier.span,
+        }
+    }
+}
+
+impl Statement<'_> {
+    pub fn span(&self) -> Option<(usize, usize)> {
+        match self {
+            Statement::Let { span, .. }
+            | Statement::Assign { span, .. }
+            | Statement::Return { span, .. }
+            | Statement::Block { span, .. } => *span,
+            Statement::Expression(expression) => expression.span(),
+        }
+    }
+}
+
+impl Expression<'_> {
+    pub fn span(&self) -> Option<(usize, usize)> {
+        match self {
+            Expression::Integer { span, .. }
+            | Expression::Float { span, .. }
+            | Expression::Boolean { span, .. }
+            | Expression::StringLiteral { span, .. }
+            | Expression::NullLiteral { span, .. }
+            | Expression::Prefix { span, .. }
+            | Expression::Infix { span, .. }
+            | Expression::If { span, .. }
+            | Expression::Ternary { span, .. }
+            | Expression::Function { span, .. }
+            | Expression::MacroLiteral { span, .. }
+            | Expression::Call { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::HashLiteral { span, .. }
+            | Expression::Index { span, .. } => *span,
+            Expression::Identifier(identifier) => identifier.span,
+            Expression::None => None,
+        }
+    }
+}
+
+impl<'a> Program<'a> {
+    /// The innermost node whose span covers the byte `offset`, for mapping a
+    /// cursor position back to the tree. Nodes without a span are skipped.
+    pub fn node_at(&self, offset: usize) -> Option<Node<'_, 'a>> {
+        self.statements
+            .iter()
+            .find_map(|statement| statement_at(statement, offset))
+    }
+}
+
+fn covers(span: Option<(usize, usize)>, offset: usize) -> bool {
+    matches!(span, Some((start, end)) if start <= offset && offset < end)
+}
+
+fn statement_at<'n, 'a>(statement: &'n Statement<'a>, offset: usize) -> Option<Node<'n, 'a>> {
+    if let Statement::Expression(expression) = statement {
+        return expression_at(expression, offset);
+    }
+    if !covers(statement.span(), offset) {
+        return None;
+    }
+
+    let inner = match statement {
+        Statement::Let { name, value, .. } | Statement::Assign { name, value, .. } => {
+            identifier_at(name, offset).or_else(|| expression_at(value, offset))
+        }
+        Statement::Return { value, .. } => expression_at(value, offset),
+        Statement::Block { statements, .. } => statements
+            .iter()
+            .find_map(|statement| statement_at(statement, offset)),
+        Statement::Expression(_) => None,
+    };
+
+    inner.or(Some(Node::Statement(statement)))
+}
+
+fn expression_at<'n, 'a>(expression: &'n Expression<'a>, offset: usize) -> Option<Node<'n, 'a>> {
+    if !covers(expression.span(), offset) {
+        return None;
+    }
+
+    let inner = match expression {
+        Expression::Integer { .. }
+        | Expression::Float { .. }
+        | Expression::Boolean { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NullLiteral { .. }
+        | Expression::Identifier(_)
+        | Expression::None => None,
+        Expression::Prefix { right, .. } => right
+            .as_ref()
+            .as_ref()
+            .and_then(|right| expression_at(right, offset)),
+        Expression::Infix { left, right, .. } => {
+            expression_at(left, offset).or_else(|| expression_at(right, offset))
+        }
+        Expression::If {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => expression_at(condition, offset)
+            .or_else(|| statement_at(consequence, offset))
+            .or_else(|| {
+                alternative
+                    .as_ref()
+                    .and_then(|alternative| statement_at(alternative, offset))
+            }),
+        Expression::Ternary {
+            condition,
+            then,
+            otherwise,
+            ..
+        } => expression_at(condition, offset)
+            .or_else(|| expression_at(then, offset))
+            .or_else(|| expression_at(otherwise, offset)),
+        Expression::Function {
+            parameters, body, ..
+        }
+        | Expression::MacroLiteral {
+            parameters, body, ..
+        } => parameters
+            .iter()
+            .find_map(|parameter| identifier_at(parameter, offset))
+            .or_else(|| statement_at(body, offset)),
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => expression_at(function, offset).or_else(|| {
+            arguments
+                .iter()
+                .find_map(|argument| expression_at(argument, offset))
+        }),
+        Expression::ArrayLiteral { elements, .. } => elements
+            .iter()
+            .find_map(|element| expression_at(element, offset)),
+        Expression::HashLiteral { pairs, .. } => pairs.iter().find_map(|(key, value)| {
+            expression_at(key, offset).or_else(|| expression_at(value, offset))
+        }),
+        Expression::Index { left, index, .. } => {
+            expression_at(left, offset).or_else(|| expression_at(index, offset))
+        }
+    };
+
+    inner.or(Some(Node::Expression(expression)))
+}
+
+fn identifier_at<'n, 'a>(identifier: &'n Identifier<'a>, offset: usize) -> Option<Node<'n, 'a>> {
+    covers(identifier.span, offset).then_some(Node::Identifier(identifier))
+}
@@ -0,0 +1,107 @@
+use super::ast::{Expression, Program, Statement};
+
+/// Walks the AST without re-matching every variant at each call site.
+///
+/// The default methods recurse into child nodes, so an implementation only
+/// needs to override the hooks it cares about and call the matching `walk_*`
+/// function to keep descending.
+pub trait Visitor<'a> {
+    fn visit_statement(&mut self, statement: &Statement<'a>) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression<'a>) {
+        walk_expression(self, expression);
+    }
+}
+
+pub fn walk_program<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, program: &Program<'a>) {
+    for statement in &program.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, statement: &Statement<'a>) {
+    match statement {
+        Statement::Let { value, .. }
+        | Statement::Assign { value, .. }
+        | Statement::Return { value, .. } => visitor.visit_expression(value),
+        Statement::Block { statements, .. } => {
+            for statement in statements {
+                visitor.visit_statement(statement);
+            }
+        }
+        Statement::Expression(expression) => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_expression<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, expression: &Expression<'a>) {
+    match expression {
+        Expression::Integer { .. }
+        | Expression::Float { .. }
+        | Expression::Boolean { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NullLiteral { .. }
+        | Expression::Identifier(_)
+        | Expression::None => {}
+        Expression::Prefix { right, .. } => {
+            if let Some(right) = right.as_ref() {
+                visitor.visit_expression(right);
+            }
+        }
+        Expression::Infix { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::If {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_statement(consequence);
+            if let Some(alternative) = alternative {
+                visitor.visit_statement(alternative);
+            }
+        }
+        Expression::Ternary {
+            condition,
+            then,
+            otherwise,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(then);
+            visitor.visit_expression(otherwise);
+        }
+        Expression::Function { body, .. } | Expression::MacroLiteral { body, .. } => {
+            visitor.visit_statement(body)
+        }
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expression(function);
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::ArrayLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::HashLiteral { pairs, .. } => {
+            for (key, value) in pairs {
+                visitor.visit_expression(key);
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Index { left, index, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(index);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    ast::{Expression, Program, Statement},
+    eval::object::Object,
+};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    // Pushes the constant at the given index of the constant pool
+    OpConstant(usize),
+    OpAdd,
+    OpSub,
+    OpMul,
+    OpDiv,
+    OpMinus,
+    // Pops the result of an expression statement off the stack
+    OpPop,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bytecode<'a> {
+    pub instructions: Vec<Instruction>,
+    pub constants: Vec<Object<'a>>,
+}
+
+#[derive(Debug, Default)]
+pub struct Compiler<'a> {
+    instructions: Vec<Instruction>,
+    constants: Vec<Object<'a>>,
+}
+
+impl<'a> Compiler<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn compile(&mut self, program: &Program<'a>) -> Result<(), String> {
+        for statement in &program.statements {
+            self.compile_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    pub fn bytecode(self) -> Bytecode<'a> {
+        Bytecode {
+            instructions: self.instructions,
+            constants: self.constants,
+        }
+    }
+
+    fn compile_statement(&mut self, statement: &Statement<'a>) -> Result<(), String> {
+        match statement {
+            Statement::Expression(expression) => {
+                self.compile_expression(expression)?;
+                self.emit(Instruction::OpPop);
+                Ok(())
+            }
+            other => Err(format!("unsupported statement: {}", other.token_literal())),
+        }
+    }
+
+    fn compile_expression(&mut self, expression: &Expression<'a>) -> Result<(), String> {
+        match expression {
+            Expression::Integer { value, .. } => {
+                let index = self.add_constant(Object::Int(*value));
+                self.emit(Instruction::OpConstant(index));
+            }
+            Expression::Prefix {
+                operator, right, ..
+            } => {
+                let right = right.as_ref().as_ref().ok_or("missing prefix operand")?;
+                self.compile_expression(right)?;
+                match *operator {
+                    "-" => self.emit(Instruction::OpMinus),
+                    _ => return Err(format!("unsupported operator: {}", operator)),
+                }
+            }
+            Expression::Infix {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                self.compile_expression(left)?;
+                self.compile_expression(right)?;
+                match *operator {
+                    "+" => self.emit(Instruction::OpAdd),
+                    "-" => self.emit(Instruction::OpSub),
+                    "*" => self.emit(Instruction::OpMul),
+                    "/" => self.emit(Instruction::OpDiv),
+                    _ => return Err(format!("unsupported operator: {}", operator)),
+                }
+            }
+            other => return Err(format!("unsupported expression: {}", other)),
+        }
+        Ok(())
+    }
+
+    fn emit(&mut self, instruction: Instruction) {
+        self.instructions.push(instruction);
+    }
+
+    fn add_constant(&mut self, constant: Object<'a>) -> usize {
+        self.constants.push(constant);
+        self.constants.len() - 1
+    }
+}
@@ -0,0 +1,5 @@
+#[cfg(test)]
+mod tests;
+
+pub mod compiler;
+pub use compiler::{Bytecode, Compiler, Instruction};
@@ -0,0 +1,82 @@
+use crate::{
+    Lexer, Parser,
+    compiler::{Compiler, Instruction},
+    eval::object::Object,
+};
+
+#[test]
+fn test_integer_arithmetic() {
+    let tests = [
+        (
+            "1 + 2",
+            vec![
+                Instruction::OpConstant(0),
+                Instruction::OpConstant(1),
+                Instruction::OpAdd,
+                Instruction::OpPop,
+            ],
+        ),
+        (
+            "1; 2",
+            vec![
+                Instruction::OpConstant(0),
+                Instruction::OpPop,
+                Instruction::OpConstant(1),
+                Instruction::OpPop,
+            ],
+        ),
+        (
+            "-1 * 2 / 1",
+            vec![
+                Instruction::OpConstant(0),
+                Instruction::OpMinus,
+                Instruction::OpConstant(1),
+                Instruction::OpMul,
+                Instruction::OpConstant(2),
+                Instruction::OpDiv,
+                Instruction::OpPop,
+            ],
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors.is_empty());
+
+        let mut compiler = Compiler::new();
+        compiler.compile(&program).expect("compilation failed");
+        let bytecode = compiler.bytecode();
+
+        assert_eq!(bytecode.instructions, expected, "input: {}", input);
+        assert!(
+            bytecode
+                .constants
+                .iter()
+                .all(|constant| matches!(constant, Object::Int(_)))
+        );
+    }
+}
+
+#[test]
+fn test_unsupported_nodes() {
+    let tests = [
+        ("let x = 5;", "unsupported statement: let"),
+        ("true", "unsupported expression: true"),
+        ("1 < 2", "unsupported operator: <"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+
+        let mut compiler = Compiler::new();
+        assert_eq!(compiler.compile(&program), Err(expected.to_string()));
+    }
+}
@@ -0,0 +1,52 @@
+use std::ops::Range;
+
+use crate::parser::ParseError;
+
+/// Renders `message` followed by the line of `source` containing `span` (a
+/// byte range), with carets under the spanned characters:
+///
+/// ```text
+/// error: expected next token to be Assign, got Int instead
+///  --> 1:7
+///   |
+/// 1 | let x 5;
+///   |       ^
+/// ```
+pub fn render_diagnostic(source: &str, span: Range<usize>, message: &str) -> String {
+    let start = span.start.min(source.len());
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[start..]
+        .find('\n')
+        .map_or(source.len(), |i| start + i);
+
+    let line = source[..start].matches('\n').count() + 1;
+    let column = source[line_start..start].chars().count() + 1;
+    let text = &source[line_start..line_end];
+
+    let end = span.end.clamp(start, line_end);
+    let width = source[start..end].chars().count().max(1);
+
+    let gutter = " ".repeat(line.to_string().len());
+    format!(
+        "error: {message}\n{gutter}--> {line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {}{}",
+        " ".repeat(column - 1),
+        "^".repeat(width)
+    )
+}
+
+impl ParseError {
+    /// Renders this error with the offending line of `source`.
+    pub fn render(&self, source: &str) -> String {
+        let line_start = source
+            .split_inclusive('\n')
+            .take(self.line.saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>();
+        let offset = source[line_start.min(source.len())..]
+            .char_indices()
+            .nth(self.column.saturating_sub(1))
+            .map_or(source.len(), |(i, _)| line_start + i);
+
+        render_diagnostic(source, offset..offset, &self.message)
+    }
+}
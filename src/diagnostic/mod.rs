@@ -0,0 +1,5 @@
+#[cfg(test)]
+mod tests;
+
+pub mod diagnostic;
+pub use diagnostic::render_diagnostic;
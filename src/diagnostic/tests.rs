@@ -0,0 +1,41 @@
+use crate::{Lexer, Parser, diagnostic::render_diagnostic};
+
+#[test]
+fn test_render_diagnostic() {
+    let source = "let x = 1;\nlet y = x + true;";
+    let start = source.find("true").unwrap();
+
+    assert_eq!(
+        render_diagnostic(source, start..start + 4, "type mismatch: INTEGER + BOOLEAN"),
+        "error: type mismatch: INTEGER + BOOLEAN
+ --> 2:13
+  |
+2 | let y = x + true;
+  |             ^^^^"
+    );
+}
+
+#[test]
+fn test_render_parse_error() {
+    let source = "let a = 1;\n\nlet b 5;\nlet c = 3;";
+
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    parser.parse_program();
+
+    let rendered = parser.errors()[0].render(source);
+    let lines = rendered.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        lines,
+        [
+            "error: expected next token to be Assign, got Int instead",
+            " --> 3:7",
+            "  |",
+            "3 | let b 5;",
+            "  |       ^",
+        ]
+    );
+    let caret = lines[4].find('^').unwrap();
+    assert_eq!(&lines[3][caret..caret + 1], "5");
+}
@@ -0,0 +1,357 @@
+use std::io::Write;
+
+use super::{
+    eval::is_truthy,
+    object::{BuiltinFunction, Object, sorted_pairs},
+};
+
+pub fn lookup<'a>(name: &str) -> Option<Object<'a>> {
+    let function = match name {
+        "len" => BuiltinFunction::new(len),
+        "first" => BuiltinFunction::new(first),
+        "last" => BuiltinFunction::new(last),
+        "rest" => BuiltinFunction::new(rest),
+        "push" => BuiltinFunction::new(push),
+        "puts" => BuiltinFunction::new(puts),
+        "type" => BuiltinFunction::new(type_of),
+        "chars" => BuiltinFunction::new(chars),
+        "split" => BuiltinFunction::new(split),
+        "keys" => BuiltinFunction::new(keys),
+        "values" => BuiltinFunction::new(values),
+        "format" => BuiltinFunction::new(format_string),
+        "sqrt" => BuiltinFunction::new(sqrt),
+        "floor" => BuiltinFunction::new(floor),
+        "ceil" => BuiltinFunction::new(ceil),
+        "abs" => BuiltinFunction::new(abs),
+        "assert" => BuiltinFunction::new(assert),
+        "assert_eq" => BuiltinFunction::new(assert_eq),
+        _ => return None,
+    };
+    Some(Object::Builtin(function))
+}
+
+fn wrong_number_of_arguments<'a>(got: usize, want: usize) -> Object<'a> {
+    Object::Error(format!(
+        "wrong number of arguments. got={}, want={}",
+        got, want
+    ))
+}
+
+fn len<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Str(s) => Object::Int(s.chars().count() as i64),
+        Object::Array(elements) => Object::Int(elements.len() as i64),
+        other => Object::Error(format!(
+            "argument to 'len' not supported, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn first<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Array(elements) => elements.first().cloned().unwrap_or(Object::Null),
+        other => Object::Error(format!(
+            "argument to 'first' must be ARRAY, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn last<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Array(elements) => elements.last().cloned().unwrap_or(Object::Null),
+        other => Object::Error(format!(
+            "argument to 'last' must be ARRAY, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn rest<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Array(elements) if elements.is_empty() => Object::Null,
+        Object::Array(elements) => Object::Array(elements[1..].into()),
+        other => Object::Error(format!(
+            "argument to 'rest' must be ARRAY, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn push<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 2 {
+        return wrong_number_of_arguments(args.len(), 2);
+    }
+
+    match &args[0] {
+        Object::Array(elements) => {
+            let mut elements = elements.to_vec();
+            elements.push(args[1].clone());
+            Object::Array(elements.into())
+        }
+        other => Object::Error(format!(
+            "argument to 'push' must be ARRAY, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn puts<'a>(args: Vec<Object<'a>>, out: &mut dyn Write) -> Object<'a> {
+    for arg in args {
+        if let Err(err) = writeln!(out, "{}", arg) {
+            return Object::Error(format!("could not write output: {}", err));
+        }
+    }
+
+    Object::Null
+}
+
+fn type_of<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    Object::Str(args[0].type_name().into())
+}
+
+fn chars<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Str(s) => Object::Array(
+            s.chars()
+                .map(|ch| Object::Str(ch.to_string().into()))
+                .collect(),
+        ),
+        other => Object::Error(format!(
+            "argument to 'chars' must be STRING, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+// An empty delimiter splits into characters rather than producing empty
+// strings at both ends.
+fn split<'a>(args: Vec<Object<'a>>, out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 2 {
+        return wrong_number_of_arguments(args.len(), 2);
+    }
+
+    match (&args[0], &args[1]) {
+        (Object::Str(_), Object::Str(delimiter)) if delimiter.is_empty() => {
+            chars(vec![args[0].clone()], out)
+        }
+        (Object::Str(s), Object::Str(delimiter)) => Object::Array(
+            s.split(delimiter.as_ref())
+                .map(|part| Object::Str(part.into()))
+                .collect(),
+        ),
+        (Object::Str(_), other) | (other, _) => Object::Error(format!(
+            "arguments to 'split' must be STRING, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+// `keys` and `values` list the pairs in key order, as a hash is displayed:
+// integers first, then booleans, then strings, each sorted by value.
+fn keys<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Hash(pairs) => Object::Array(
+            sorted_pairs(pairs)
+                .into_iter()
+                .map(|(key, _)| key.to_object())
+                .collect(),
+        ),
+        other => Object::Error(format!(
+            "argument to 'keys' must be HASH, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn values<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Hash(pairs) => Object::Array(
+            sorted_pairs(pairs)
+                .into_iter()
+                .map(|(_, value)| value.clone())
+                .collect(),
+        ),
+        other => Object::Error(format!(
+            "argument to 'values' must be HASH, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+// Replaces each `{}` in the template with the next argument; `{{` and `}}`
+// produce literal braces.
+fn format_string<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    let Some((template, values)) = args.split_first() else {
+        return Object::Error("wrong number of arguments. got=0, want at least 1".to_string());
+    };
+    let Object::Str(template) = template else {
+        return Object::Error(format!(
+            "first argument to 'format' must be STRING, got {}",
+            template.type_name()
+        ));
+    };
+
+    let mut result = String::new();
+    let mut placeholders = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match (ch, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                result.push(ch);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                if let Some(value) = values.get(placeholders) {
+                    result.push_str(&value.to_string());
+                }
+                placeholders += 1;
+            }
+            ('{', _) | ('}', _) => {
+                return Object::Error(format!("unmatched '{}' in format string", ch));
+            }
+            _ => result.push(ch),
+        }
+    }
+
+    if placeholders != values.len() {
+        return Object::Error(format!(
+            "format string has {} placeholders but {} arguments were given",
+            placeholders,
+            values.len()
+        ));
+    }
+
+    Object::Str(result.into())
+}
+
+fn sqrt<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    let x = match &args[0] {
+        Object::Int(i) => *i as f64,
+        Object::Float(x) => *x,
+        other => {
+            return Object::Error(format!(
+                "argument to 'sqrt' must be INTEGER or FLOAT, got {}",
+                other.type_name()
+            ));
+        }
+    };
+
+    if x < 0.0 {
+        return Object::Error(format!("cannot take sqrt of negative number {}", args[0]));
+    }
+    Object::Float(x.sqrt())
+}
+
+fn floor<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    round_to_integer("floor", args, f64::floor)
+}
+
+fn ceil<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    round_to_integer("ceil", args, f64::ceil)
+}
+
+// Integers pass through unchanged; floats are rounded with `round` and must
+// land inside the integer range.
+fn round_to_integer<'a>(name: &str, args: Vec<Object<'a>>, round: fn(f64) -> f64) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Int(i) => Object::Int(*i),
+        Object::Float(x) => {
+            let rounded = round(*x);
+            if rounded.is_finite() && rounded >= i64::MIN as f64 && rounded < i64::MAX as f64 {
+                Object::Int(rounded as i64)
+            } else {
+                Object::Error(format!("'{}' result out of integer range: {}", name, x))
+            }
+        }
+        other => Object::Error(format!(
+            "argument to '{}' must be INTEGER or FLOAT, got {}",
+            name,
+            other.type_name()
+        )),
+    }
+}
+
+fn abs<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    match &args[0] {
+        Object::Int(i) => match i.checked_abs() {
+            Some(value) => Object::Int(value),
+            None => Object::Error("integer overflow".to_string()),
+        },
+        Object::Float(x) => Object::Float(x.abs()),
+        other => Object::Error(format!(
+            "argument to 'abs' must be INTEGER or FLOAT, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn assert<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 1 {
+        return wrong_number_of_arguments(args.len(), 1);
+    }
+
+    if is_truthy(args[0].clone()) {
+        Object::Null
+    } else {
+        Object::Error(format!("assertion failed: {}", args[0]))
+    }
+}
+
+fn assert_eq<'a>(args: Vec<Object<'a>>, _out: &mut dyn Write) -> Object<'a> {
+    if args.len() != 2 {
+        return wrong_number_of_arguments(args.len(), 2);
+    }
+
+    if args[0] == args[1] {
+        Object::Null
+    } else {
+        Object::Error(format!("assertion failed: {} != {}", args[0], args[1]))
+    }
+}
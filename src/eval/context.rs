@@ -0,0 +1,44 @@
+use std::io::Write;
+
+/// Default limit on nested function calls before evaluation gives up.
+///
+/// Evaluation grows the host stack as it needs, so this only stops runaway
+/// recursion; it is high enough for the prelude's recursive `map`, `filter`
+/// and `reduce` to walk arrays of several thousand elements.
+pub const DEFAULT_MAX_DEPTH: usize = 10_000;
+
+/// State threaded through evaluation alongside the environment.
+pub struct Context<'w> {
+    /// Where `puts` and other output-producing builtins write to.
+    pub out: &'w mut dyn Write,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'w> Context<'w> {
+    pub fn new(out: &'w mut dyn Write) -> Self {
+        Self {
+            out,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Records entry into a function call, returning `false` if that would
+    /// exceed the maximum depth.
+    pub fn enter(&mut self) -> bool {
+        if self.depth >= self.max_depth {
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    pub fn exit(&mut self) {
+        self.depth -= 1;
+    }
+}
@@ -0,0 +1,180 @@
+use std::{cell::RefCell, collections::HashMap, fmt, io::Write, rc::Rc};
+
+use super::object::{BuiltinFunction, Object};
+use crate::ast::{Identifier, Statement};
+
+/// A value as stored in a scope.
+///
+/// A function defined in the scope it is bound in would hold that scope, and
+/// the scope would hold it back, so neither is ever freed. Such functions are
+/// stored without their environment, which is put back when they are read.
+///
+/// This only covers a function bound where it was defined. A closure bound in
+/// some other scope, such as one returned from a call, holds the call's scope,
+/// which holds the scope the closure is bound in through `outer`. That cycle
+/// lasts until the binding is replaced or the scope is [cleared].
+///
+/// [cleared]: Environment::clear
+enum Binding<'a> {
+    Value(Object<'a>),
+    OwnFunction {
+        parameters: Rc<[Identifier<'a>]>,
+        body: Rc<Statement<'a>>,
+    },
+    OwnMacro {
+        parameters: Rc<[Identifier<'a>]>,
+        body: Rc<Statement<'a>>,
+    },
+}
+
+#[derive(Default)]
+struct Scope<'a> {
+    store: HashMap<String, Binding<'a>>,
+    outer: Option<Environment<'a>>,
+}
+
+/// A shared handle to a scope of bindings.
+///
+/// Cloning an `Environment` does not copy its bindings: the clone refers to
+/// the same scope. Closures capture their defining environment this way, so
+/// they see bindings made after they were created, including their own name,
+/// which is what makes recursion work.
+#[derive(Clone, Default)]
+pub struct Environment<'a>(Rc<RefCell<Scope<'a>>>);
+
+impl<'a> Environment<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new, empty scope nested inside `outer`. Lookups fall through to
+    /// `outer`, but `set` only ever binds in the new scope, so a `let` here
+    /// shadows an outer binding of the same name without changing it.
+    pub fn with_outer(outer: Environment<'a>) -> Self {
+        Self(Rc::new(RefCell::new(Scope {
+            store: HashMap::new(),
+            outer: Some(outer),
+        })))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Object<'a>> {
+        let scope = self.0.borrow();
+        match scope.store.get(name) {
+            Some(binding) => Some(self.resolve(binding)),
+            None => scope.outer.as_ref().and_then(|outer| outer.get(name)),
+        }
+    }
+
+    pub fn assign(&mut self, name: &str, value: Object<'a>) -> Option<Object<'a>> {
+        let mut scope = self.0.borrow_mut();
+        if let Some(slot) = scope.store.get_mut(name) {
+            *slot = self.bind(value.clone());
+            return Some(value);
+        }
+
+        scope
+            .outer
+            .as_mut()
+            .and_then(|outer| outer.assign(name, value))
+    }
+
+    pub fn bindings(&self) -> Vec<(String, Object<'a>)> {
+        self.0
+            .borrow()
+            .store
+            .iter()
+            .map(|(name, binding)| (name.clone(), self.resolve(binding)))
+            .collect()
+    }
+
+    /// Whether a macro is bound in this scope or any enclosing one.
+    pub fn has_macros(&self) -> bool {
+        let scope = self.0.borrow();
+        scope.store.values().any(|binding| {
+            matches!(
+                binding,
+                Binding::OwnMacro { .. } | Binding::Value(Object::Macro { .. })
+            )
+        }) || scope.outer.as_ref().is_some_and(Environment::has_macros)
+    }
+
+    /// Removes every binding from this scope, releasing the closures bound in
+    /// it and the scopes they captured. An environment that is done with
+    /// should be cleared before it is dropped, since a closure bound in it may
+    /// have captured a scope that refers back to it.
+    pub fn clear(&mut self) {
+        let store = std::mem::take(&mut self.0.borrow_mut().store);
+        // Dropped once the scope is no longer borrowed, as the closures may
+        // release scopes that refer back to it
+        drop(store);
+    }
+
+    /// Binds a native function under `name` in this scope, so embedders can
+    /// expose their own functions to Monkey code. Like any binding it shadows
+    /// a built-in function of the same name.
+    pub fn register_builtin(
+        &mut self,
+        name: &str,
+        function: impl Fn(Vec<Object<'a>>, &mut dyn Write) -> Object<'a> + 'a,
+    ) {
+        self.set(name, Object::Builtin(BuiltinFunction::new(function)));
+    }
+
+    pub fn set(&mut self, name: &str, value: Object<'a>) -> Object<'a> {
+        self.0
+            .borrow_mut()
+            .store
+            .insert(name.to_string(), self.bind(value.clone()));
+        value
+    }
+
+    fn bind(&self, value: Object<'a>) -> Binding<'a> {
+        match value {
+            Object::Function {
+                parameters,
+                body,
+                env,
+            } if Rc::ptr_eq(&env.0, &self.0) => Binding::OwnFunction { parameters, body },
+            Object::Macro {
+                parameters,
+                body,
+                env,
+            } if Rc::ptr_eq(&env.0, &self.0) => Binding::OwnMacro { parameters, body },
+            value => Binding::Value(value),
+        }
+    }
+
+    fn resolve(&self, binding: &Binding<'a>) -> Object<'a> {
+        match binding {
+            Binding::Value(value) => value.clone(),
+            Binding::OwnFunction { parameters, body } => Object::Function {
+                parameters: parameters.clone(),
+                body: body.clone(),
+                env: self.clone(),
+            },
+            Binding::OwnMacro { parameters, body } => Object::Macro {
+                parameters: parameters.clone(),
+                body: body.clone(),
+                env: self.clone(),
+            },
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn strong_count(&self) -> usize {
+        Rc::strong_count(&self.0)
+    }
+}
+
+// Functions hold the environment they were defined in, which may in turn hold
+// the function, so only the binding names are printed to avoid recursing
+// forever.
+impl fmt::Debug for Environment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = self.0.borrow();
+        f.debug_struct("Environment")
+            .field("names", &scope.store.keys().collect::<Vec<_>>())
+            .field("outer", &scope.outer)
+            .finish()
+    }
+}
@@ -1,125 +1,564 @@
-use super::object::Object;
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    rc::Rc,
+};
+
+use super::{
+    builtins,
+    context::Context,
+    environment::Environment,
+    macro_expansion::{define_macros, expand_macros, needs_expansion},
+    object::{HashKey, Object},
+    quote::quote,
+};
 use crate::ast::{Program, Statement, ast};
 
-pub fn eval(node: ast::Statement) -> Option<Object> {
+pub fn eval<'a>(
+    node: &ast::Statement<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
     match node {
-        ast::Statement::Let { token, name, value } => Some(todo!()),
+        ast::Statement::Let { name, value, .. } => {
+            let val = eval_expression(value, env, ctx);
+            if is_error(&val) {
+                return val;
+            }
+            env.set(name.value, val);
+            Object::Null
+        }
+        ast::Statement::Assign { name, value, .. } => {
+            let val = eval_expression(value, env, ctx);
+            if is_error(&val) {
+                return val;
+            }
+            match env.assign(name.value, val) {
+                Some(_) => Object::Null,
+                None => new_error(format!("identifier not found: {}", name.value)),
+            }
+        }
         ast::Statement::Return { value, .. } => {
-            let val = eval(Statement::Expression(value));
-            Some(Object::ReturnValue(Box::new(val)))
-        }
-        ast::Statement::Block { statements, .. } => Some(eval_block_statement(&statements)),
-        ast::Statement::Expression(expression) => match expression {
-            ast::Expression::Integer { value, .. } => Some(Object::Int(value)),
-            ast::Expression::Boolean { value, .. } => Some(Object::Bool(value)),
-            ast::Expression::Prefix {
-                right, operator, ..
-            } => {
-                let rt = eval(ast::Statement::Expression(right.unwrap()))?;
-                eval_prefix_expression(operator, rt)
-            }
-            ast::Expression::Infix {
-                operator,
-                right,
-                left,
-                ..
-            } => {
-                let lt = eval(ast::Statement::Expression(*left))?;
-                let rt = eval(ast::Statement::Expression(*right))?;
-                eval_infix_expression(operator, lt, rt)
-            }
-            ast::Expression::If {
-                condition,
-                consequence,
-                alternative,
-                ..
-            } => eval_if_expression(condition, consequence, alternative),
-            ast::Expression::Function {
-                token,
-                parameters,
-                body,
-            } => Some(todo!()),
-            ast::Expression::Call {
-                token,
-                function,
-                arguments,
-            } => Some(todo!()),
-            ast::Expression::Identifier(identifier) => Some(todo!()),
-            ast::Expression::None => Some(todo!()),
+            let val = eval_expression(value, env, ctx);
+            if is_error(&val) {
+                return val;
+            }
+            Object::ReturnValue(Box::new(val))
+        }
+        // A bare block gets its own scope, so its bindings don't leak out
+        ast::Statement::Block { statements, .. } => {
+            eval_block_statement(statements, &mut Environment::with_outer(env.clone()), ctx)
+        }
+        ast::Statement::Expression(expression) => eval_expression(expression, env, ctx),
+    }
+}
+
+pub fn eval_expression<'a>(
+    expression: &ast::Expression<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    crate::grow_stack(|| eval_expression_on_stack(expression, env, ctx))
+}
+
+fn eval_expression_on_stack<'a>(
+    expression: &ast::Expression<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    match expression {
+        ast::Expression::Integer { value, .. } => Object::Int(*value),
+        ast::Expression::Float { value, .. } => Object::Float(*value),
+        ast::Expression::Boolean { value, .. } => Object::Bool(*value),
+        ast::Expression::StringLiteral { value, .. } => Object::Str(value.as_str().into()),
+        ast::Expression::NullLiteral { .. } => Object::Null,
+        ast::Expression::Prefix {
+            right, operator, ..
+        } => {
+            let Some(right) = right.as_ref() else {
+                return new_error(format!("missing operand for prefix operator: {}", operator));
+            };
+            let rt = eval_expression(right, env, ctx);
+            if is_error(&rt) {
+                return rt;
+            }
+            eval_prefix_expression(operator, rt)
+        }
+        ast::Expression::Infix {
+            operator,
+            right,
+            left,
+            ..
+        } => {
+            let lt = eval_expression(left, env, ctx);
+            if is_error(&lt) {
+                return lt;
+            }
+            let rt = eval_expression(right, env, ctx);
+            if is_error(&rt) {
+                return rt;
+            }
+            eval_infix_expression(operator, lt, rt)
+        }
+        ast::Expression::If {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => eval_if_expression(condition, consequence, alternative.as_deref(), env, ctx),
+        ast::Expression::Ternary {
+            condition,
+            then,
+            otherwise,
+            ..
+        } => {
+            let condition = eval_expression(condition, env, ctx);
+            if is_error(&condition) {
+                return condition;
+            }
+            if is_truthy(condition) {
+                eval_expression(then, env, ctx)
+            } else {
+                eval_expression(otherwise, env, ctx)
+            }
+        }
+        ast::Expression::Function {
+            parameters, body, ..
+        } => Object::Function {
+            parameters: parameters.as_slice().into(),
+            body: Rc::new(body.as_ref().clone()),
+            env: env.clone(),
+        },
+        ast::Expression::MacroLiteral {
+            parameters, body, ..
+        } => Object::Macro {
+            parameters: parameters.as_slice().into(),
+            body: Rc::new(body.as_ref().clone()),
+            env: env.clone(),
         },
+        ast::Expression::Call {
+            function,
+            arguments,
+            ..
+        } if arguments.len() == 1
+            && matches!(function.as_ref(), ast::Expression::Identifier(identifier) if identifier.value == "quote") =>
+        {
+            quote(arguments[0].clone(), env, ctx)
+        }
+        ast::Expression::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            let function = eval_expression(function, env, ctx);
+            if is_error(&function) {
+                return function;
+            }
+            match eval_expressions(arguments, env, ctx) {
+                Ok(args) => apply_function(function, args, ctx),
+                Err(err) => err,
+            }
+        }
+        ast::Expression::ArrayLiteral { elements, .. } => {
+            match eval_expressions(elements, env, ctx) {
+                Ok(elements) => Object::Array(elements.into()),
+                Err(err) => err,
+            }
+        }
+        ast::Expression::HashLiteral { pairs, .. } => eval_hash_literal(pairs, env, ctx),
+        ast::Expression::Index { left, index, .. } => {
+            let left = eval_expression(left, env, ctx);
+            if is_error(&left) {
+                return left;
+            }
+            let index = eval_expression(index, env, ctx);
+            if is_error(&index) {
+                return index;
+            }
+            eval_index_expression(left, index)
+        }
+        ast::Expression::Identifier(identifier) => eval_identifier(identifier, env),
+        // Left behind by a bare `return;`
+        ast::Expression::None => Object::Null,
+    }
+}
+
+// Function bodies and `if` branches run their block directly in `env`, which
+// for functions is already the call's own scope.
+pub(crate) fn eval_body<'a>(
+    body: &Statement<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    match body {
+        Statement::Block { statements, .. } => eval_block_statement(statements, env, ctx),
+        other => eval(other, env, ctx),
     }
 }
 
-fn eval_block_statement(statements: &[Statement<'_>]) -> Object {
-    let mut result = Some(Object::Null);
+fn eval_block_statement<'a>(
+    statements: &[Statement<'a>],
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    let mut result = Object::Null;
 
     for stmt in statements.iter() {
-        let res = eval(stmt.clone());
-        match res {
-            Some(obj @ Object::ReturnValue(_)) => return obj,
-            Some(obj) => result = Some(obj),
-            None => continue,
+        result = eval(stmt, env, ctx);
+        if let Object::ReturnValue(_) | Object::Error(_) = result {
+            return result;
         }
     }
-    result.unwrap()
+    result
 }
 
-fn eval_if_expression(
-    condition: Box<ast::Expression<'_>>,
-    consequence: Box<Statement<'_>>,
-    alternative: Option<Box<Statement<'_>>>,
-) -> Option<Object> {
-    let condition = eval(Statement::Expression(*condition))?;
+fn eval_if_expression<'a>(
+    condition: &ast::Expression<'a>,
+    consequence: &Statement<'a>,
+    alternative: Option<&Statement<'a>>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    let condition = eval_expression(condition, env, ctx);
+    if is_error(&condition) {
+        return condition;
+    }
 
     if is_truthy(condition) {
-        eval(*consequence)
+        eval_body(consequence, env, ctx)
     } else {
-        alternative.map(|alt| eval(*alt)).unwrap_or(None)
+        alternative
+            .map(|alt| eval_body(alt, env, ctx))
+            .unwrap_or(Object::Null)
+    }
+}
+
+fn eval_identifier<'a>(identifier: &ast::Identifier<'a>, env: &Environment<'a>) -> Object<'a> {
+    env.get(identifier.value)
+        .or_else(|| builtins::lookup(identifier.value))
+        .unwrap_or_else(|| Object::Error(format!("identifier not found: {}", identifier.value)))
+}
+
+fn eval_expressions<'a>(
+    expressions: &[ast::Expression<'a>],
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Result<Vec<Object<'a>>, Object<'a>> {
+    let mut result = Vec::with_capacity(expressions.len());
+
+    for expression in expressions {
+        let evaluated = eval_expression(expression, env, ctx);
+        if is_error(&evaluated) {
+            return Err(evaluated);
+        }
+        result.push(evaluated);
+    }
+
+    Ok(result)
+}
+
+fn apply_function<'a>(
+    function: Object<'a>,
+    args: Vec<Object<'a>>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    match function {
+        Object::Function {
+            parameters,
+            body,
+            env,
+        } => {
+            let mut extended_env = Environment::with_outer(env);
+            for (parameter, arg) in parameters.iter().zip(args) {
+                extended_env.set(parameter.value, arg);
+            }
+
+            if !ctx.enter() {
+                return new_error("maximum recursion depth exceeded".to_string());
+            }
+            let result = eval_body(&body, &mut extended_env, ctx);
+            ctx.exit();
+
+            match result {
+                Object::ReturnValue(value) => *value,
+                obj => obj,
+            }
+        }
+        Object::Builtin(builtin) => builtin.call(args, ctx.out),
+        other => new_error(format!("not a function: {}", other.type_name())),
+    }
+}
+
+fn eval_index_expression<'a>(left: Object<'a>, index: Object<'a>) -> Object<'a> {
+    match (left, index) {
+        (Object::Array(elements), Object::Int(i)) => usize::try_from(i)
+            .ok()
+            .and_then(|i| elements.get(i).cloned())
+            .unwrap_or(Object::Null),
+        (Object::Str(s), Object::Int(i)) => usize::try_from(i)
+            .ok()
+            .and_then(|i| s.chars().nth(i))
+            .map(|ch| Object::Str(ch.to_string().into()))
+            .unwrap_or(Object::Null),
+        (Object::Hash(pairs), key) => match HashKey::from_object(&key) {
+            Some(hash_key) => pairs.get(&hash_key).cloned().unwrap_or(Object::Null),
+            None => new_error(format!("unusable as hash key: {}", key.type_name())),
+        },
+        (left, _) => new_error(format!(
+            "index operator not supported: {}",
+            left.type_name()
+        )),
+    }
+}
+
+fn eval_hash_literal<'a>(
+    pairs: &[(ast::Expression<'a>, ast::Expression<'a>)],
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    let mut hash = HashMap::with_capacity(pairs.len());
+
+    for (key, value) in pairs {
+        let key = eval_expression(key, env, ctx);
+        if is_error(&key) {
+            return key;
+        }
+        let Some(hash_key) = HashKey::from_object(&key) else {
+            return new_error(format!("unusable as hash key: {}", key.type_name()));
+        };
+
+        let value = eval_expression(value, env, ctx);
+        if is_error(&value) {
+            return value;
+        }
+        hash.insert(hash_key, value);
     }
+
+    Object::Hash(hash.into())
 }
 
-fn is_truthy(object: Object) -> bool {
+fn new_error<'a>(message: String) -> Object<'a> {
+    Object::Error(message)
+}
+
+fn is_error(object: &Object<'_>) -> bool {
+    matches!(object, Object::Error(_))
+}
+
+// Only `false` and `null` are falsy. In particular `0`, `""` and `[]` are
+// truthy, as in the book.
+pub(crate) fn is_truthy(object: Object) -> bool {
     match object {
-        Object::Int(_) => true,
         Object::Bool(b) => b,
         Object::Null => false,
-        _ => true,
+        Object::Int(_)
+        | Object::Float(_)
+        | Object::Str(_)
+        | Object::Array(_)
+        | Object::Hash(_)
+        | Object::Function { .. }
+        | Object::Builtin(_)
+        | Object::Quote(_)
+        | Object::Macro { .. } => true,
+        Object::ReturnValue(value) => is_truthy(*value),
+        Object::Error(_) => false,
     }
 }
 
-fn eval_infix_expression(operator: &str, lt: Object, rt: Object) -> Option<Object> {
+fn eval_infix_expression<'a>(operator: &str, lt: Object<'a>, rt: Object<'a>) -> Object<'a> {
     match (lt, rt) {
         (Object::Int(l), Object::Int(r)) => eval_integer_infix_operation(operator, l, r),
-        _ => None,
+        (Object::Float(l), Object::Float(r)) => eval_float_infix_operation(operator, l, r),
+        (Object::Int(l), Object::Float(r)) => eval_float_infix_operation(operator, l as f64, r),
+        (Object::Float(l), Object::Int(r)) => eval_float_infix_operation(operator, l, r as f64),
+        (Object::Bool(l), Object::Bool(r)) => eval_boolean_infix_operation(operator, l, r),
+        (Object::Str(l), Object::Str(r)) => eval_string_infix_operation(operator, &l, &r),
+        (lt @ Object::Null, rt) | (lt, rt @ Object::Null) if operator == "==" => {
+            Object::Bool(lt == rt)
+        }
+        (lt @ Object::Null, rt) | (lt, rt @ Object::Null) if operator == "!=" => {
+            Object::Bool(lt != rt)
+        }
+        (Object::Array(l), Object::Array(r)) => eval_array_infix_operation(operator, &l, &r),
+        (Object::Array(elements), Object::Int(count)) if operator == "*" => {
+            repeat_array(&elements, count)
+        }
+        // Values of different types are never equal, so `==` and `!=` answer
+        // rather than reporting a type mismatch.
+        (lt, rt) if lt.type_name() != rt.type_name() && operator == "==" => Object::Bool(false),
+        (lt, rt) if lt.type_name() != rt.type_name() && operator == "!=" => Object::Bool(true),
+        (lt, rt) if lt.type_name() != rt.type_name() => new_error(format!(
+            "type mismatch: {} {} {}",
+            lt.type_name(),
+            operator,
+            rt.type_name()
+        )),
+        (lt, rt) => new_error(format!(
+            "unknown operator: {} {} {}",
+            lt.type_name(),
+            operator,
+            rt.type_name()
+        )),
+    }
+}
+
+fn eval_integer_infix_operation<'a>(operator: &str, l: i64, r: i64) -> Object<'a> {
+    match operator {
+        "+" => checked_integer(l.checked_add(r)),
+        "-" => checked_integer(l.checked_sub(r)),
+        "*" => checked_integer(l.checked_mul(r)),
+        "/" | "%" if r == 0 => new_error("division by zero".to_string()),
+        "/" => checked_integer(l.checked_div(r)),
+        "%" => checked_integer(l.checked_rem(r)),
+        "**" if r < 0 => new_error(format!("negative exponent: {}", r)),
+        "**" => checked_integer(u32::try_from(r).ok().and_then(|r| l.checked_pow(r))),
+        "&" => Object::Int(l & r),
+        "|" => Object::Int(l | r),
+        "^" => Object::Int(l ^ r),
+        "<<" | ">>" if !(0..i64::BITS as i64).contains(&r) => {
+            new_error(format!("invalid shift amount: {}", r))
+        }
+        "<<" => Object::Int(l << r),
+        ">>" => Object::Int(l >> r),
+        "<" => Object::Bool(l < r),
+        ">" => Object::Bool(l > r),
+        "<=" => Object::Bool(l <= r),
+        ">=" => Object::Bool(l >= r),
+        "!=" => Object::Bool(l != r),
+        "==" => Object::Bool(l == r),
+        _ => new_error(format!("unknown operator: INTEGER {} INTEGER", operator)),
+    }
+}
+
+fn eval_float_infix_operation<'a>(operator: &str, l: f64, r: f64) -> Object<'a> {
+    match operator {
+        "+" => Object::Float(l + r),
+        "-" => Object::Float(l - r),
+        "*" => Object::Float(l * r),
+        "/" | "%" if r == 0.0 => new_error("division by zero".to_string()),
+        "/" => Object::Float(l / r),
+        "%" => Object::Float(l % r),
+        "**" => Object::Float(l.powf(r)),
+        "<" => Object::Bool(l < r),
+        ">" => Object::Bool(l > r),
+        "<=" => Object::Bool(l <= r),
+        ">=" => Object::Bool(l >= r),
+        "!=" => Object::Bool(l != r),
+        "==" => Object::Bool(l == r),
+        _ => new_error(format!("unknown operator: FLOAT {} FLOAT", operator)),
     }
 }
 
-fn eval_integer_infix_operation(operator: &str, l: i64, r: i64) -> Option<Object> {
+fn checked_integer<'a>(result: Option<i64>) -> Object<'a> {
+    match result {
+        Some(value) => Object::Int(value),
+        None => new_error("integer overflow".to_string()),
+    }
+}
+
+fn eval_boolean_infix_operation<'a>(operator: &str, l: bool, r: bool) -> Object<'a> {
+    match operator {
+        "==" => Object::Bool(l == r),
+        "!=" => Object::Bool(l != r),
+        _ => new_error(format!("unknown operator: BOOLEAN {} BOOLEAN", operator)),
+    }
+}
+
+fn eval_array_infix_operation<'a>(
+    operator: &str,
+    l: &[Object<'a>],
+    r: &[Object<'a>],
+) -> Object<'a> {
     match operator {
-        "+" => Some(Object::Int(l + r)),
-        "-" => Some(Object::Int(l - r)),
-        "*" => Some(Object::Int(l * r)),
-        "/" => Some(Object::Int(l / r)),
-        "<" => Some(Object::Bool(l < r)),
-        ">" => Some(Object::Bool(l > r)),
-        "!=" => Some(Object::Bool(l != r)),
-        "==" => Some(Object::Bool(l == r)),
-        _ => None,
+        "+" => Object::Array(l.iter().chain(r).cloned().collect()),
+        "==" => Object::Bool(l == r),
+        "!=" => Object::Bool(l != r),
+        _ => new_error(format!("unknown operator: ARRAY {} ARRAY", operator)),
     }
 }
 
-pub fn eval_program(program: &mut Program) -> Object {
-    let mut result = Some(Object::Null);
+/// The most elements `*` will build an array of, so a huge count is reported
+/// instead of exhausting memory.
+const MAX_REPEATED_LEN: usize = 10_000_000;
+
+// A count of zero or less gives an empty array
+fn repeat_array<'a>(elements: &[Object<'a>], count: i64) -> Object<'a> {
+    let count = usize::try_from(count).unwrap_or(0);
+    match elements.len().checked_mul(count) {
+        Some(len) if len <= MAX_REPEATED_LEN => {}
+        _ => {
+            return new_error(format!(
+                "array too large: {} * {} is more than {} elements",
+                elements.len(),
+                count,
+                MAX_REPEATED_LEN
+            ));
+        }
+    }
+    // Checked against the length above, but an empty array can still come
+    // with a huge count
+    Object::Array(
+        std::iter::repeat_n(elements, count.min(MAX_REPEATED_LEN))
+            .flatten()
+            .cloned()
+            .collect(),
+    )
+}
+
+fn eval_string_infix_operation<'a>(operator: &str, l: &str, r: &str) -> Object<'a> {
+    match operator {
+        "+" => Object::Str(format!("{}{}", l, r).into()),
+        "==" => Object::Bool(l == r),
+        "!=" => Object::Bool(l != r),
+        _ => new_error(format!("unknown operator: STRING {} STRING", operator)),
+    }
+}
+
+/// Evaluates `program`, returning the final value or the message of the error
+/// that stopped evaluation.
+pub fn eval_program<'a>(
+    program: &mut Program<'a>,
+    env: &mut Environment<'a>,
+) -> Result<Object<'a>, String> {
+    eval_program_with_output(program, env, &mut io::stdout())
+}
+
+/// Evaluates `program`, writing anything produced by `puts` to `out`.
+pub fn eval_program_with_output<'a>(
+    program: &mut Program<'a>,
+    env: &mut Environment<'a>,
+    out: &mut dyn Write,
+) -> Result<Object<'a>, String> {
+    eval_program_with_context(program, env, &mut Context::new(out))
+}
+
+/// Evaluates `program` with an explicit [`Context`], e.g. to change the
+/// maximum call depth.
+pub fn eval_program_with_context<'a>(
+    program: &mut Program<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Result<Object<'a>, String> {
+    if needs_expansion(program, env) {
+        define_macros(program, env);
+        expand_macros(program, env, ctx);
+    }
+
+    let mut result = Object::Null;
 
     for stmt in program.statements.iter() {
-        let return_value = eval(stmt.clone());
-        if let Some(Object::ReturnValue(_)) = return_value {
-            return return_value.unwrap();
+        result = eval(stmt, env, ctx);
+        if let Object::ReturnValue(_) | Object::Error(_) = result {
+            break;
         }
-        result = return_value;
     }
 
-    result.unwrap_or(Object::Null)
+    // A top-level `return` stops the program and yields its value
+    match result {
+        Object::Error(message) => Err(message),
+        Object::ReturnValue(value) => Ok(*value),
+        result => Ok(result),
+    }
 }
 
 // pub fn eval_statements(statements: &[Statement]) -> Object {
@@ -133,26 +572,49 @@ pub fn eval_program(program: &mut Program) -> Object {
 //     }
 // }
 
-pub fn eval_prefix_expression(operator: &str, right: Object) -> Option<Object> {
+pub fn eval_prefix_expression<'a>(operator: &str, right: Object<'a>) -> Object<'a> {
     match operator {
         "-" => eval_minus_prefix_operator_expression(right),
-        "!" => Some(eval_bang_operator_expression(right)),
-        _ => None,
+        "+" => eval_plus_prefix_operator_expression(right),
+        "!" => eval_bang_operator_expression(right),
+        _ => new_error(format!(
+            "unknown operator: {}{}",
+            operator,
+            right.type_name()
+        )),
+    }
+}
+
+fn eval_minus_prefix_operator_expression<'a>(right: Object<'a>) -> Object<'a> {
+    match right {
+        Object::Int(i) => checked_integer(i.checked_neg()),
+        Object::Float(x) => Object::Float(-x),
+        other => new_error(format!("unknown operator: -{}", other.type_name())),
     }
 }
 
-fn eval_minus_prefix_operator_expression(right: Object) -> Option<Object> {
+fn eval_plus_prefix_operator_expression<'a>(right: Object<'a>) -> Object<'a> {
     match right {
-        Object::Int(i) => Some(Object::Int(-i)),
-        _ => None,
+        Object::Int(_) | Object::Float(_) => right,
+        other => new_error(format!("unknown operator: +{}", other.type_name())),
     }
 }
 
-fn eval_bang_operator_expression(right: Object) -> Object {
+// `!x` is the negation of x's truthiness, so it is only true for false and null
+fn eval_bang_operator_expression<'a>(right: Object<'a>) -> Object<'a> {
     match right {
         Object::Bool(r) => Object::Bool(!r),
-        Object::Int(_) => Object::Bool(false),
         Object::Null => Object::Bool(true),
-        _ => Object::Null,
+        Object::Int(_)
+        | Object::Float(_)
+        | Object::Str(_)
+        | Object::Array(_)
+        | Object::Hash(_)
+        | Object::Function { .. }
+        | Object::Builtin(_)
+        | Object::Quote(_)
+        | Object::Macro { .. } => Object::Bool(false),
+        Object::ReturnValue(value) => eval_bang_operator_expression(*value),
+        Object::Error(_) => right,
     }
 }
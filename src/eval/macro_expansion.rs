@@ -0,0 +1,95 @@
+use std::mem;
+
+use super::{context::Context, environment::Environment, eval::eval_body, object::Object};
+use crate::ast::{Expression, Program, Statement, modify::modify_program};
+
+/// Whether `program` defines a macro or one is already bound in `env`, so
+/// expansion could change anything. Most programs have no macros at all and
+/// skip rebuilding their tree.
+pub fn needs_expansion(program: &Program<'_>, env: &Environment<'_>) -> bool {
+    program.statements.iter().any(is_macro_definition) || env.has_macros()
+}
+
+fn is_macro_definition(statement: &Statement<'_>) -> bool {
+    matches!(
+        statement,
+        Statement::Let {
+            value: Expression::MacroLiteral { .. },
+            ..
+        }
+    )
+}
+
+/// Removes top-level `let name = macro(...) { ... };` statements from
+/// `program` and binds the macros in `env`.
+pub fn define_macros<'a>(program: &mut Program<'a>, env: &mut Environment<'a>) {
+    program.statements.retain(|statement| match statement {
+        Statement::Let {
+            name,
+            value: Expression::MacroLiteral {
+                parameters, body, ..
+            },
+            ..
+        } => {
+            env.set(
+                name.value,
+                Object::Macro {
+                    parameters: parameters.as_slice().into(),
+                    body: body.as_ref().clone().into(),
+                    env: env.clone(),
+                },
+            );
+            false
+        }
+        _ => true,
+    });
+}
+
+/// Replaces every call to a macro bound in `env` with the quoted expression
+/// the macro returns. Its arguments are passed to it unevaluated, as quotes.
+///
+/// A macro that doesn't return a quote leaves the call in place, so it fails
+/// at evaluation time with "not a function: MACRO".
+pub fn expand_macros<'a>(
+    program: &mut Program<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) {
+    let expanded = modify_program(mem::take(program), &mut |expression| {
+        let Expression::Call {
+            function,
+            arguments,
+            ..
+        } = &expression
+        else {
+            return expression;
+        };
+        let Expression::Identifier(identifier) = function.as_ref() else {
+            return expression;
+        };
+        let Some(Object::Macro {
+            parameters,
+            body,
+            env: macro_env,
+        }) = env.get(identifier.value)
+        else {
+            return expression;
+        };
+
+        let mut extended_env = Environment::with_outer(macro_env);
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            extended_env.set(parameter.value, Object::Quote(argument.clone().into()));
+        }
+
+        match eval_body(&body, &mut extended_env, ctx) {
+            Object::Quote(quoted) => quoted.as_ref().clone(),
+            Object::ReturnValue(value) => match *value {
+                Object::Quote(quoted) => quoted.as_ref().clone(),
+                _ => expression,
+            },
+            _ => expression,
+        }
+    });
+
+    *program = expanded;
+}
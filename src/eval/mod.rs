@@ -1,6 +1,15 @@
+pub mod builtins;
+pub mod context;
+pub use context::{Context, DEFAULT_MAX_DEPTH};
+pub mod environment;
+pub use environment::Environment;
 pub mod eval;
+pub mod macro_expansion;
 pub mod object;
-pub use eval::eval_program;
+pub mod prelude;
+pub use prelude::with_prelude;
+pub mod quote;
+pub use eval::{eval_program, eval_program_with_context, eval_program_with_output};
 
 #[cfg(test)]
 mod tests;
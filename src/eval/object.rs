@@ -1,20 +1,202 @@
 use core::fmt;
+use std::{collections::HashMap, io::Write, rc::Rc};
 
+use super::environment::Environment;
+use crate::ast::{Expression, Identifier, Statement};
+
+/// A native function callable from Monkey. It gets the evaluated arguments and
+/// the output `puts` writes to, and may capture state of its own, such as an
+/// embedder's handle to the host.
+#[derive(Clone)]
+pub struct BuiltinFunction<'a>(Rc<NativeFn<'a>>);
+
+type NativeFn<'a> = dyn Fn(Vec<Object<'a>>, &mut dyn Write) -> Object<'a> + 'a;
+
+impl<'a> BuiltinFunction<'a> {
+    pub fn new(function: impl Fn(Vec<Object<'a>>, &mut dyn Write) -> Object<'a> + 'a) -> Self {
+        Self(Rc::new(function))
+    }
+
+    pub fn call(&self, args: Vec<Object<'a>>, out: &mut dyn Write) -> Object<'a> {
+        (self.0)(args, out)
+    }
+}
+
+impl fmt::Debug for BuiltinFunction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BuiltinFunction")
+    }
+}
+
+// Strings, arrays, hashes and functions are reference counted so that passing
+// them around the evaluator is a cheap pointer copy rather than a deep clone.
 #[derive(Debug, Clone)]
-pub enum Object {
+pub enum Object<'a> {
     Int(i64),
+    Float(f64),
     Bool(bool),
+    Str(Rc<str>),
+    Array(Rc<[Object<'a>]>),
+    Hash(Rc<HashMap<HashKey, Object<'a>>>),
     Null,
-    ReturnValue(Box<Option<Object>>),
+    ReturnValue(Box<Object<'a>>),
+    Error(String),
+    Function {
+        parameters: Rc<[Identifier<'a>]>,
+        body: Rc<Statement<'a>>,
+        env: Environment<'a>,
+    },
+    Builtin(BuiltinFunction<'a>),
+    // An unevaluated expression produced by `quote`
+    Quote(Rc<Expression<'a>>),
+    Macro {
+        parameters: Rc<[Identifier<'a>]>,
+        body: Rc<Statement<'a>>,
+        env: Environment<'a>,
+    },
+}
+
+impl Object<'_> {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Int(_) => "INTEGER",
+            Object::Float(_) => "FLOAT",
+            Object::Bool(_) => "BOOLEAN",
+            Object::Str(_) => "STRING",
+            Object::Array(_) => "ARRAY",
+            Object::Hash(_) => "HASH",
+            Object::Null => "NULL",
+            Object::ReturnValue(_) => "RETURN_VALUE",
+            Object::Error(_) => "ERROR",
+            Object::Function { .. } => "FUNCTION",
+            Object::Builtin(_) => "BUILTIN",
+            Object::Quote(_) => "QUOTE",
+            Object::Macro { .. } => "MACRO",
+        }
+    }
+}
+
+// Functions, macros and builtins are never equal, even to themselves; everything else
+// compares structurally.
+impl PartialEq for Object<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Object::Int(l), Object::Int(r)) => l == r,
+            (Object::Float(l), Object::Float(r)) => l == r,
+            (Object::Bool(l), Object::Bool(r)) => l == r,
+            (Object::Str(l), Object::Str(r)) => l == r,
+            (Object::Array(l), Object::Array(r)) => l == r,
+            (Object::Hash(l), Object::Hash(r)) => l == r,
+            (Object::Null, Object::Null) => true,
+            (Object::ReturnValue(l), Object::ReturnValue(r)) => l == r,
+            (Object::Error(l), Object::Error(r)) => l == r,
+            (Object::Quote(l), Object::Quote(r)) => l == r,
+            _ => false,
+        }
+    }
 }
 
-impl fmt::Display for Object {
+impl fmt::Display for Object<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Object::Int(i) => write!(f, "{}", i),
+            Object::Float(x) => write!(f, "{}", x),
             Object::Bool(b) => write!(f, "{}", b),
+            Object::Str(s) => write!(f, "{}", s),
+            Object::Array(elements) => write!(
+                f,
+                "[{}]",
+                elements
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Object::Hash(pairs) => write!(
+                f,
+                "{{{}}}",
+                sorted_pairs(pairs)
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
             Object::Null => write!(f, "nil"),
-            Object::ReturnValue(object) => write!(f, "{}", object.clone().unwrap()),
+            Object::ReturnValue(object) => write!(f, "{}", object),
+            Object::Error(message) => write!(f, "ERROR: {}", message),
+            Object::Function {
+                parameters, body, ..
+            } => write!(
+                f,
+                "fn({}) {{ {} }}",
+                parameters
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                body
+            ),
+            Object::Builtin(_) => write!(f, "builtin function"),
+            Object::Quote(expression) => write!(f, "QUOTE({})", expression),
+            Object::Macro {
+                parameters, body, ..
+            } => write!(
+                f,
+                "macro({}) {{ {} }}",
+                parameters
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                body
+            ),
         }
     }
 }
+
+// Keys order integers first, then booleans, then strings, each by value
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HashKey {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+}
+
+impl HashKey {
+    pub fn from_object(object: &Object<'_>) -> Option<Self> {
+        match object {
+            Object::Int(i) => Some(HashKey::Int(*i)),
+            Object::Bool(b) => Some(HashKey::Bool(*b)),
+            Object::Str(s) => Some(HashKey::Str(s.to_string())),
+            _ => None,
+        }
+    }
+
+    pub fn to_object<'a>(&self) -> Object<'a> {
+        match self {
+            HashKey::Int(i) => Object::Int(*i),
+            HashKey::Bool(b) => Object::Bool(*b),
+            HashKey::Str(s) => Object::Str(s.as_str().into()),
+        }
+    }
+}
+
+impl fmt::Display for HashKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashKey::Int(i) => write!(f, "{}", i),
+            HashKey::Bool(b) => write!(f, "{}", b),
+            HashKey::Str(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// The entries of a hash in [`HashKey`] order, so they can be shown
+/// reproducibly.
+pub fn sorted_pairs<'h, 'a>(
+    pairs: &'h HashMap<HashKey, Object<'a>>,
+) -> Vec<(&'h HashKey, &'h Object<'a>)> {
+    let mut sorted: Vec<_> = pairs.iter().collect();
+    sorted.sort_by_key(|(key, _)| *key);
+    sorted
+}
@@ -0,0 +1,53 @@
+use std::io;
+
+use super::{environment::Environment, eval::eval_program_with_output};
+use crate::{lexer::Lexer, parser::parser::Parser};
+
+/// Library functions written in Monkey itself, evaluated into the base
+/// environment before user code runs.
+pub const PRELUDE: &str = "
+let map = fn(arr, f) {
+    let iter = fn(arr, accumulated) {
+        if (len(arr) == 0) {
+            accumulated
+        } else {
+            iter(rest(arr), push(accumulated, f(first(arr))))
+        }
+    };
+    iter(arr, []);
+};
+
+let reduce = fn(arr, initial, f) {
+    let iter = fn(arr, result) {
+        if (len(arr) == 0) {
+            result
+        } else {
+            iter(rest(arr), f(result, first(arr)))
+        }
+    };
+    iter(arr, initial);
+};
+
+let filter = fn(arr, f) {
+    let iter = fn(arr, accumulated) {
+        if (len(arr) == 0) {
+            accumulated
+        } else {
+            let x = first(arr);
+            iter(rest(arr), if (f(x)) { push(accumulated, x) } else { accumulated })
+        }
+    };
+    iter(arr, []);
+};
+";
+
+/// Defines the [`PRELUDE`] functions (`map`, `reduce` and `filter`) in `env`.
+pub fn with_prelude(env: &mut Environment<'_>) {
+    let mut parser = Parser::new(Lexer::new(PRELUDE));
+    let mut program = parser.parse_program().expect("prelude should parse");
+    assert!(parser.errors.is_empty(), "prelude should parse");
+
+    if let Err(message) = eval_program_with_output(&mut program, env, &mut io::sink()) {
+        panic!("prelude failed to evaluate: {}", message)
+    }
+}
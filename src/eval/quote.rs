@@ -0,0 +1,80 @@
+use super::{context::Context, environment::Environment, eval::eval_expression, object::Object};
+use crate::{
+    ast::{Expression, modify::modify_expression},
+    lexer::token::{Token, TokenType},
+};
+
+/// Implements the `quote` special form: returns `expression` unevaluated,
+/// except for `unquote(...)` calls inside it, which are evaluated and spliced
+/// back into the tree.
+pub fn quote<'a>(
+    expression: Expression<'a>,
+    env: &mut Environment<'a>,
+    ctx: &mut Context<'_>,
+) -> Object<'a> {
+    let expression = modify_expression(expression, &mut |expression| match expression {
+        Expression::Call {
+            function,
+            mut arguments,
+            ..
+        } if arguments.len() == 1 && is_unquote(&function) => {
+            let unquoted = eval_expression(&arguments.remove(0), env, ctx);
+            object_to_expression(unquoted)
+        }
+        other => other,
+    });
+
+    Object::Quote(expression.into())
+}
+
+fn is_unquote(function: &Expression<'_>) -> bool {
+    matches!(function, Expression::Identifier(identifier) if identifier.value == "unquote")
+}
+
+// Objects spliced into a quote have no source text, so their tokens own a
+// literal built from the value.
+fn object_to_expression(object: Object<'_>) -> Expression<'_> {
+    match object {
+        Object::Int(value) => Expression::Integer {
+            token: Token::new(TokenType::Int, value.to_string()),
+            value,
+            span: None,
+        },
+        Object::Float(value) => Expression::Float {
+            token: Token::new(TokenType::Float, value.to_string()),
+            value,
+            span: None,
+        },
+        Object::Bool(value) => Expression::Boolean {
+            token: Token::new(
+                if value {
+                    TokenType::True
+                } else {
+                    TokenType::False
+                },
+                if value { "true" } else { "false" },
+            ),
+            value,
+            span: None,
+        },
+        Object::Str(value) => Expression::StringLiteral {
+            token: Token::new(TokenType::String, escape(&value)),
+            value: value.to_string(),
+            span: None,
+        },
+        Object::Quote(expression) => expression.as_ref().clone(),
+        _ => Expression::NullLiteral {
+            token: Token::new(TokenType::Null, "null"),
+            span: None,
+        },
+    }
+}
+
+// String tokens keep their escapes verbatim, see `lexer::unescape`
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
@@ -1,4 +1,21 @@
-use crate::{Lexer, Parser, eval::eval::eval_program};
+use std::rc::Rc;
+
+use crate::{
+    Lexer, Parser,
+    ast::{Expression, Program, Statement},
+    eval::{
+        Context, Environment,
+        eval::{eval_program, eval_program_with_output},
+        macro_expansion::{define_macros, expand_macros},
+        object::{HashKey, Object},
+    },
+};
+
+// Folds an evaluation error back into `Object::Error` so expectations can be
+// written against a single value.
+fn eval_value<'a>(program: &mut Program<'a>, env: &mut Environment<'a>) -> Object<'a> {
+    eval_program(program, env).unwrap_or_else(Object::Error)
+}
 
 #[test]
 pub fn test_int_eval() {
@@ -15,9 +32,9 @@ pub fn test_int_eval() {
         assert!(parser.errors.is_empty());
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
-        assert_eq!(format!("{}", actual), format!("{}", expected))
+        assert_eq!(actual, Object::Int(expected))
     }
 }
 
@@ -36,9 +53,9 @@ pub fn test_bool_eval() {
         assert!(parser.errors.is_empty());
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
-        assert_eq!(format!("{}", actual), format!("{}", expected))
+        assert_eq!(actual, Object::Bool(expected))
     }
 }
 
@@ -57,9 +74,9 @@ pub fn test_prefix_operator_eval() {
         assert!(parser.errors.is_empty());
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
-        assert_eq!(format!("{}", actual), format!("{}", expected))
+        assert_eq!(actual, Object::Bool(expected))
     }
 }
 
@@ -78,9 +95,9 @@ pub fn test_minus_operator() {
         assert!(parser.errors.is_empty());
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
-        assert_eq!(format!("{}", actual), format!("{}", expected))
+        assert_eq!(actual, Object::Int(expected))
     }
 }
 
@@ -91,7 +108,7 @@ pub fn test_infix_operators() {
         ("10", 10),
         ("-5", -5),
         ("-10", -10),
-        ("5 + 5 + 5 + 5 - 10)", 10),
+        ("5 + 5 + 5 + 5 - 10", 10),
         ("2 * 2 * 2 * 2 * 2", 32),
         (" - 50 + 100 + -50", 0),
         ("5 * 2 + 10", 20),
@@ -114,9 +131,9 @@ pub fn test_infix_operators() {
         assert!(parser.errors.is_empty());
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
-        assert_eq!(format!("{}", actual), format!("{}", expected))
+        assert_eq!(actual, Object::Int(expected))
     }
 }
 
@@ -142,7 +159,7 @@ pub fn test_if_expression() {
         assert!(parser.errors.is_empty());
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
         assert_eq!(format!("{}", actual), format!("{}", expected))
     }
@@ -179,7 +196,7 @@ fn test_return_statements() {
 
         let mut program = program.unwrap();
         dbg!(program.clone());
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
         assert_eq!(format!("{}", actual), format!("{}", expected))
     }
@@ -244,7 +261,7 @@ fn test_early_returns() {
         );
 
         let mut program = program.unwrap();
-        let actual = eval_program(&mut program);
+        let actual = eval_value(&mut program, &mut Environment::new());
 
         assert_eq!(
             format!("{}", actual),
@@ -256,3 +273,2281 @@ fn test_early_returns() {
         );
     }
 }
+
+#[test]
+fn test_let_statements() {
+    let inputs_expected = [
+        ("let a = 5; a;", 5),
+        ("let a = 5 * 5; a;", 25),
+        ("let a = 5; let b = a; b;", 5),
+        ("let a = 5; let b = a; let c = a + b + 5; c;", 15),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty());
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, Object::Int(expected))
+    }
+}
+
+#[test]
+fn test_unbound_identifier() {
+    let lexer = Lexer::new("foobar;");
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(format!("{}", actual), "ERROR: identifier not found: foobar")
+}
+
+#[test]
+fn test_function_application() {
+    let inputs_expected = [
+        ("let identity = fn(x) { x; }; identity(5);", 5),
+        ("let identity = fn(x) { return x; }; identity(5);", 5),
+        ("let double = fn(x) { x * 2; }; double(5);", 10),
+        ("let add = fn(x, y) { x + y; }; add(2, 3);", 5),
+        ("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));", 20),
+        ("fn(x) { x; }(5)", 5),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty());
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, Object::Int(expected))
+    }
+}
+
+#[test]
+fn test_closures() {
+    let input = "
+        let newAdder = fn(x) { fn(y) { x + y }; };
+        let add2 = newAdder(2);
+        add2(3);
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(actual, Object::Int(5))
+}
+
+#[test]
+fn test_call_scopes_are_freed() {
+    let input = "
+        let f = fn(n) { let g = fn() { n }; g() };
+        let count = fn(n) { if (n > 0) { count(n - 1) } else { f(n) } };
+        count(10) + len(map([1, 2, 3], fn(x) { x }));
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let mut env = Environment::new();
+    crate::with_prelude(&mut env);
+    let actual = eval_value(&mut program, &mut env);
+
+    assert_eq!(actual, Object::Int(3));
+    // Every call scope refers to `env`, so a scope kept alive by a function
+    // bound inside it would show up here
+    assert_eq!(env.strong_count(), 1);
+
+    // A closure returned from a call, or assigned to an outer binding, holds
+    // the call's scope, which refers back to `env` until it's cleared
+    let input = "
+        let adder = fn(x) { fn(y) { x + y } };
+        let add2 = adder(2);
+        let h = 0;
+        let f = fn(x) { h = fn() { x }; };
+        f(5);
+        add2(3) + adder(4)(5) + h();
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut env);
+
+    assert_eq!(actual, Object::Int(19));
+    assert_eq!(env.strong_count(), 3);
+
+    env.clear();
+    assert_eq!(env.strong_count(), 1);
+}
+
+#[test]
+fn test_error_handling() {
+    let inputs_expected = [
+        ("5 + true;", "type mismatch: INTEGER + BOOLEAN"),
+        ("5 + true; 5;", "type mismatch: INTEGER + BOOLEAN"),
+        ("-true;", "unknown operator: -BOOLEAN"),
+        ("true + false;", "unknown operator: BOOLEAN + BOOLEAN"),
+        ("5; true + false; 5", "unknown operator: BOOLEAN + BOOLEAN"),
+        (
+            "if (10 > 1) { true + false; }",
+            "unknown operator: BOOLEAN + BOOLEAN",
+        ),
+        (
+            "if (10 > 1) { if (10 > 1) { return true + false; } return 1; }",
+            "unknown operator: BOOLEAN + BOOLEAN",
+        ),
+        ("foobar", "identifier not found: foobar"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty());
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        match actual {
+            Object::Error(message) => assert_eq!(message, expected, "input: {}", input),
+            other => panic!("expected error object for {}, got {}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_boolean_infix_operators() {
+    let inputs_expected = [
+        ("true == true", true),
+        ("false == false", true),
+        ("true == false", false),
+        ("true != false", true),
+        ("false != true", true),
+        ("(1 < 2) == true", true),
+        ("(1 < 2) == false", false),
+        ("(1 > 2) == true", false),
+        ("(1 > 2) == false", true),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty());
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, Object::Bool(expected))
+    }
+}
+
+#[test]
+fn test_string_literal() {
+    let lexer = Lexer::new(r#""foobar""#);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(actual, Object::Str("foobar".into()))
+}
+
+#[test]
+fn test_string_concatenation() {
+    let lexer = Lexer::new(r#""Hello" + " " + "World""#);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(actual, Object::Str("Hello World".into()))
+}
+
+#[test]
+fn test_string_unknown_operator() {
+    let lexer = Lexer::new(r#""a" - "b""#);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(
+        actual,
+        Object::Error("unknown operator: STRING - STRING".to_string())
+    )
+}
+
+#[test]
+fn test_array_literals() {
+    let inputs_expected = [
+        ("[]", "[]"),
+        ("[1, 2 * 2, 3 + 3]", "[1, 4, 6]"),
+        ("let a = 2; [a, a * a, true]", "[2, 4, true]"),
+        ("[1, -true, 5 + true]", "ERROR: unknown operator: -BOOLEAN"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty());
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected)
+    }
+}
+
+#[test]
+fn test_array_index_expressions() {
+    let inputs_expected = [
+        ("[1, 2, 3][0]", "1"),
+        ("[1, 2, 3][1]", "2"),
+        ("[1, 2, 3][2]", "3"),
+        ("let i = 0; [1][i];", "1"),
+        ("[1, 2, 3][1 + 1];", "3"),
+        ("let a = [1, 2]; a[0] + a[1];", "3"),
+        ("[1][5]", "nil"),
+        ("[1, 2, 3][-1]", "nil"),
+        ("1[0]", "ERROR: index operator not supported: INTEGER"),
+        ("[1, 2][true]", "ERROR: index operator not supported: ARRAY"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty());
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_hash_literals() {
+    let input = r#"
+        let two = "two";
+        {
+            "one": 10 - 9,
+            two: 1 + 1,
+            "thr" + "ee": 6 / 2,
+            4: 4,
+            true: 5,
+            false: 6
+        }
+        "#;
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    let Object::Hash(pairs) = actual else {
+        panic!("expected hash object, got {}", actual);
+    };
+
+    let expected = [
+        (HashKey::Str("one".to_string()), 1),
+        (HashKey::Str("two".to_string()), 2),
+        (HashKey::Str("three".to_string()), 3),
+        (HashKey::Int(4), 4),
+        (HashKey::Bool(true), 5),
+        (HashKey::Bool(false), 6),
+    ];
+
+    assert_eq!(pairs.len(), expected.len());
+    for (key, value) in expected {
+        assert_eq!(pairs[&key], Object::Int(value));
+    }
+}
+
+#[test]
+fn test_hash_index_expressions() {
+    let inputs_expected = [
+        (r#"{"foo": 5}["foo"]"#, "5"),
+        (r#"{"foo": 5}["bar"]"#, "nil"),
+        (r#"let key = "foo"; {"foo": 5}[key]"#, "5"),
+        (r#"{}["foo"]"#, "nil"),
+        ("{5: 5}[5]", "5"),
+        ("{true: 5}[true]", "5"),
+        ("{false: 5}[false]", "5"),
+        (
+            r#"{"name": "Monkey"}[fn(x) { x }];"#,
+            "ERROR: unusable as hash key: FUNCTION",
+        ),
+        ("{fn(x) { x }: 1}", "ERROR: unusable as hash key: FUNCTION"),
+        ("{[1]: 1}", "ERROR: unusable as hash key: ARRAY"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_builtin_len() {
+    let inputs_expected = [
+        (r#"len("")"#, "0"),
+        (r#"len("four")"#, "4"),
+        (r#"len("hello world")"#, "11"),
+        ("len([1, 2, 3])", "3"),
+        ("len([])", "0"),
+        (
+            "len(1)",
+            "ERROR: argument to 'len' not supported, got INTEGER",
+        ),
+        (
+            r#"len("one", "two")"#,
+            "ERROR: wrong number of arguments. got=2, want=1",
+        ),
+        ("let len = fn(x) { 42 }; len([1]);", "42"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_array_builtins() {
+    let inputs_expected = [
+        ("first([1, 2, 3])", "1"),
+        ("first([])", "nil"),
+        ("last([1, 2, 3])", "3"),
+        ("last([])", "nil"),
+        ("rest([1, 2, 3])", "[2, 3]"),
+        ("rest([])", "nil"),
+        ("push([1, 2], 3)", "[1, 2, 3]"),
+        ("let a = [1, 2]; push(a, 3); a", "[1, 2]"),
+        (
+            "first(1)",
+            "ERROR: argument to 'first' must be ARRAY, got INTEGER",
+        ),
+        (
+            "last(1)",
+            "ERROR: argument to 'last' must be ARRAY, got INTEGER",
+        ),
+        (
+            "rest(1)",
+            "ERROR: argument to 'rest' must be ARRAY, got INTEGER",
+        ),
+        (
+            "push(1, 1)",
+            "ERROR: argument to 'push' must be ARRAY, got INTEGER",
+        ),
+        (
+            "first([1], [2])",
+            "ERROR: wrong number of arguments. got=2, want=1",
+        ),
+        (
+            "push([1])",
+            "ERROR: wrong number of arguments. got=1, want=2",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_map_with_array_builtins() {
+    let input = "
+        let map = fn(arr, f) {
+            let iter = fn(arr, accumulated, iter) {
+                if (len(arr) == 0) {
+                    accumulated
+                } else {
+                    iter(rest(arr), push(accumulated, f(first(arr))), iter);
+                }
+            };
+            iter(arr, [], iter);
+        };
+        let double = fn(x) { x * 2 };
+        map([1, 2, 3], double);
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(
+        actual,
+        Object::Array(vec![Object::Int(2), Object::Int(4), Object::Int(6)].into())
+    )
+}
+
+#[test]
+fn test_builtin_puts() {
+    let lexer = Lexer::new(r#"puts("hello", 42)"#);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let mut output = Vec::new();
+    let actual = eval_program_with_output(&mut program, &mut Environment::new(), &mut output)
+        .unwrap_or_else(Object::Error);
+
+    assert_eq!(actual, Object::Null);
+    assert_eq!(String::from_utf8(output).unwrap(), "hello\n42\n");
+}
+
+#[test]
+fn test_assign_statements() {
+    let inputs_expected = [
+        ("let x = 5; x = 10; x;", "10"),
+        ("let x = 5; x = x * 2 + 1; x;", "11"),
+        ("let x = 1; let f = fn() { x = 2; x }; f();", "2"),
+        ("let f = fn(x) { x = x + 1; x }; f(41);", "42"),
+        ("x = 10;", "ERROR: identifier not found: x"),
+        (
+            "let f = fn() { y = 1; }; f();",
+            "ERROR: identifier not found: y",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_assign_updates_enclosing_environment() {
+    let mut outer = Environment::new();
+    outer.set("x", Object::Int(1));
+
+    let mut inner = Environment::with_outer(outer.clone());
+    inner.set("y", Object::Int(2));
+
+    assert!(inner.assign("x", Object::Int(10)).is_some());
+    assert!(inner.assign("y", Object::Int(20)).is_some());
+    assert!(inner.assign("z", Object::Int(30)).is_none());
+
+    assert_eq!(inner.get("x"), Some(Object::Int(10)));
+    assert_eq!(inner.get("y"), Some(Object::Int(20)));
+    assert!(inner.get("z").is_none());
+
+    assert_eq!(outer.get("x"), Some(Object::Int(10)));
+    assert!(outer.get("y").is_none());
+}
+
+#[test]
+fn test_enclosed_environment_shadowing() {
+    let mut outer = Environment::new();
+    outer.set("x", Object::Int(1));
+
+    {
+        let mut inner = Environment::with_outer(outer.clone());
+        assert_eq!(inner.get("x"), Some(Object::Int(1)));
+
+        inner.set("x", Object::Int(2));
+        assert_eq!(inner.get("x"), Some(Object::Int(2)));
+        assert_eq!(outer.get("x"), Some(Object::Int(1)));
+    }
+
+    assert_eq!(outer.get("x"), Some(Object::Int(1)));
+}
+
+#[test]
+fn test_let_shadows_outer_binding() {
+    let inputs_expected = [
+        ("let x = 1; let f = fn() { x }; f()", "1"),
+        ("let x = 1; let f = fn() { let x = 2; x }; f()", "2"),
+        ("let x = 1; let f = fn() { let x = 2; x }; f(); x", "1"),
+        ("let x = 1; { let x = 2; x + 1 }; x", "1"),
+        ("let x = 1; let f = fn(x) { x * 10 }; f(5) + x", "51"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_recursive_fibonacci() {
+    let input = "
+        let fib = fn(n, fib) {
+            if (n < 2) {
+                return n;
+            }
+            fib(n - 1, fib) + fib(n - 2, fib);
+        };
+        fib(25, fib);
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    assert_eq!(actual, Object::Int(75025))
+}
+
+#[test]
+fn test_top_level_return_values() {
+    let inputs_expected = [
+        ("return;", "nil"),
+        ("return 5;", "5"),
+        ("return; 5;", "nil"),
+        ("let f = fn() { return; }; f();", "nil"),
+        ("let f = fn() { return 5; 10 }; f();", "5"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_modulo_operator() {
+    let inputs_expected = [
+        ("10 % 3", "1"),
+        ("9 % 3", "0"),
+        ("2 + 10 % 3", "3"),
+        ("(2 + 10) % 5", "2"),
+        ("2 * 7 % 4", "2"),
+        ("10 % 0", "ERROR: division by zero"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_division_by_zero() {
+    let inputs_expected = [
+        ("1 / 0", "ERROR: division by zero"),
+        ("let x = 0; 10 / x", "ERROR: division by zero"),
+        ("1 / 0; 5;", "ERROR: division by zero"),
+        ("let f = fn() { 1 / 0; 5 }; f();", "ERROR: division by zero"),
+        ("10 / 2", "5"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_division_by_zero_stops_evaluation() {
+    let lexer = Lexer::new(r#"1 / 0; puts("unreachable");"#);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let mut output = Vec::new();
+    let actual = eval_program_with_output(&mut program, &mut Environment::new(), &mut output)
+        .unwrap_or_else(Object::Error);
+
+    assert_eq!(format!("{}", actual), "ERROR: division by zero");
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_integer_overflow() {
+    let inputs_expected = [
+        ("9223372036854775807 + 1", "ERROR: integer overflow"),
+        ("9223372036854775807 + 0", "9223372036854775807"),
+        ("-9223372036854775807 - 2", "ERROR: integer overflow"),
+        ("-9223372036854775807 - 1", "-9223372036854775808"),
+        ("9223372036854775807 * 2", "ERROR: integer overflow"),
+        ("4611686018427387904 * 2", "ERROR: integer overflow"),
+        ("4611686018427387903 * 2", "9223372036854775806"),
+        ("(-9223372036854775807 - 1) / -1", "ERROR: integer overflow"),
+        ("(-9223372036854775807 - 1) % -1", "ERROR: integer overflow"),
+        ("-(-9223372036854775807 - 1)", "ERROR: integer overflow"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_float_arithmetic() {
+    let inputs_expected = [
+        ("3.14", "3.14"),
+        ("10.0 / 4.0", "2.5"),
+        ("1 + 2.5", "3.5"),
+        ("1 + 2.0", "3"),
+        ("2.5 * 2", "5"),
+        ("-1.5", "-1.5"),
+        ("1.5 < 2", "true"),
+        ("2.0 == 2.0", "true"),
+        ("1.0 / 0", "ERROR: division by zero"),
+        ("1.5 + true", "ERROR: type mismatch: FLOAT + BOOLEAN"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_run() {
+    let actual = crate::run("let add = fn(a, b) { a + b }; add(2, 3);");
+
+    match actual {
+        Ok(object) => assert_eq!(object, Object::Int(5)),
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn test_run_with_syntax_error() {
+    let actual = crate::run("let x 5;");
+
+    match actual {
+        Ok(object) => panic!("expected parse errors, got {}", object),
+        Err(crate::RunError::Runtime(message)) => panic!("expected parse errors, got {}", message),
+        Err(crate::RunError::Parse(errors)) => assert_eq!(
+            errors[0].to_string(),
+            "expected next token to be Assign, got Int instead at line 1, col 7"
+        ),
+    }
+}
+
+#[test]
+fn test_object_display() {
+    let inputs_expected = [
+        ("5", "5"),
+        ("2.5", "2.5"),
+        ("true", "true"),
+        (r#""raw string""#, "raw string"),
+        ("[1, \"two\", [3]]", "[1, two, [3]]"),
+        ("fn(x, y) { x + y; }", "fn(x, y) { (x + y) }"),
+        ("fn() { 1 }", "fn() { 1 }"),
+        ("len", "builtin function"),
+        ("if (false) { 1 }", "nil"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_object_equality() {
+    assert_eq!(Object::Int(5), Object::Int(5));
+    assert_ne!(Object::Int(5), Object::Int(6));
+    assert_ne!(Object::Int(1), Object::Bool(true));
+    assert_eq!(Object::Null, Object::Null);
+    assert_eq!(Object::Str("monkey".into()), Object::Str("monkey".into()));
+    assert_eq!(
+        Object::Array(vec![Object::Int(1), Object::Array(vec![Object::Null].into())].into()),
+        Object::Array(vec![Object::Int(1), Object::Array(vec![Object::Null].into())].into())
+    );
+    assert_ne!(
+        Object::Array(vec![Object::Int(1)].into()),
+        Object::Array(vec![Object::Int(1), Object::Int(2)].into())
+    );
+
+    let function = crate::run("fn(x) { x }").unwrap();
+    assert_ne!(function, function.clone());
+
+    let builtin = crate::run("len").unwrap();
+    assert_ne!(builtin, builtin.clone());
+}
+
+#[test]
+fn test_object_type_names() {
+    let mut hash = std::collections::HashMap::new();
+    hash.insert(HashKey::Int(1), Object::Null);
+
+    let objects = [
+        (Object::Int(1), "INTEGER"),
+        (Object::Float(1.5), "FLOAT"),
+        (Object::Bool(true), "BOOLEAN"),
+        (Object::Str("s".into()), "STRING"),
+        (Object::Array(vec![].into()), "ARRAY"),
+        (Object::Hash(hash.into()), "HASH"),
+        (Object::Null, "NULL"),
+        (Object::ReturnValue(Box::new(Object::Null)), "RETURN_VALUE"),
+        (Object::Error("oops".to_string()), "ERROR"),
+        (crate::run("fn(x) { x }").unwrap(), "FUNCTION"),
+        (crate::run("len").unwrap(), "BUILTIN"),
+    ];
+
+    for (object, expected) in objects {
+        assert_eq!(object.type_name(), expected);
+    }
+}
+
+#[test]
+fn test_arrays_are_shared_across_calls() {
+    let input = "
+        let nest = fn(depth, inner, nest) {
+            if (depth < 1) {
+                return inner;
+            }
+            nest(depth - 1, [inner], nest);
+        };
+        let deep = nest(50, [1, 2, 3], nest);
+        let pass = fn(n, value, pass) {
+            if (n < 1) {
+                return value;
+            }
+            pass(n - 1, value, pass);
+        };
+        pass(100, deep, pass);
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let mut env = Environment::new();
+    let actual = eval_value(&mut program, &mut env);
+
+    let (Object::Array(actual), Some(Object::Array(deep))) = (actual, env.get("deep")) else {
+        panic!("expected both values to be arrays");
+    };
+    assert!(
+        Rc::ptr_eq(&actual, &deep),
+        "array was copied instead of shared"
+    );
+}
+
+#[test]
+fn test_null_literal() {
+    let inputs_expected = [
+        ("null", Object::Null),
+        ("let x = null; x", Object::Null),
+        ("null == null", Object::Bool(true)),
+        ("null != null", Object::Bool(false)),
+        ("null != 5", Object::Bool(true)),
+        ("5 == null", Object::Bool(false)),
+        ("if (false) { 10 } == null", Object::Bool(true)),
+        (
+            "null + 1",
+            Object::Error("type mismatch: NULL + INTEGER".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_prefix_plus_operator() {
+    let inputs_expected = [
+        ("+5", Object::Int(5)),
+        ("+-5", Object::Int(-5)),
+        ("+2.5", Object::Float(2.5)),
+        ("1 - +2", Object::Int(-1)),
+        (
+            "+true",
+            Object::Error("unknown operator: +BOOLEAN".to_string()),
+        ),
+        (
+            "+\"a\"",
+            Object::Error("unknown operator: +STRING".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_direct_and_mutual_recursion() {
+    let inputs_expected = [
+        (
+            "let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }; fib(10);",
+            Object::Int(55),
+        ),
+        (
+            "
+            let isEven = fn(n) { if (n == 0) { true } else { isOdd(n - 1) } };
+            let isOdd = fn(n) { if (n == 0) { false } else { isEven(n - 1) } };
+            [isEven(10), isOdd(7), isEven(3)];
+            ",
+            Object::Array(vec![Object::Bool(true), Object::Bool(true), Object::Bool(false)].into()),
+        ),
+        (
+            "
+            let counter = fn() { let count = 0; fn() { count = count + 1; count } };
+            let next = counter();
+            next(); next(); next();
+            ",
+            Object::Int(3),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_maximum_recursion_depth() {
+    let inputs_expected = [
+        (
+            "let f = fn(n) { f(n + 1) }; f(0);",
+            "ERROR: maximum recursion depth exceeded",
+        ),
+        (
+            "let f = fn(n) { 1 + f(n + 1) }; let x = f(0); 5",
+            "ERROR: maximum recursion depth exceeded",
+        ),
+        (
+            "let f = fn(n) { if (n < 1) { 0 } else { f(n - 1) } }; f(49);",
+            "0",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let actual = match crate::run_with_max_depth(input, 50) {
+            Ok(object) => object.to_string(),
+            Err(err) => err.to_string(),
+        };
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+
+    let actual = crate::run_with_max_depth("let f = fn() { 1 }; f();", 0);
+    assert_eq!(
+        actual,
+        Err(crate::RunError::Runtime(
+            "maximum recursion depth exceeded".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_long_flat_expression() {
+    let input = vec!["1"; 1500].join(" + ");
+
+    let actual = crate::run(&input).map(|object| object.to_string());
+
+    assert_eq!(actual, Ok("1500".to_string()));
+}
+
+#[test]
+fn test_deeply_nested_expression_on_small_stack() {
+    let inputs_expected = [
+        (format!("{}1{}", "(".repeat(900), ")".repeat(900)), "1"),
+        (format!("{}1", "- ".repeat(900)), "1"),
+        (format!("{}true", "!".repeat(901)), "false"),
+        (
+            format!(
+                "let id = macro(x) {{ x }}; id(1) + {}",
+                vec!["1"; 10_000].join(" + ")
+            ),
+            "10001",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let actual = std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(move || crate::run(&input).map(|object| object.to_string()))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(actual, Ok(expected.to_string()));
+    }
+}
+
+#[test]
+fn test_default_recursion_depth_on_small_stack() {
+    let inputs = [
+        "let f = fn(n) { f(n + 1) }; f(0);",
+        "let f = fn(n) { if (n > 0) { [[[[[(((f(n + 1))))]]]]] } else { 0 } }; f(1);",
+    ];
+
+    for input in inputs {
+        let actual = std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(move || crate::run(input).map(|object| object.to_string()))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(
+            actual,
+            Err(crate::RunError::Runtime(
+                "maximum recursion depth exceeded".to_string()
+            )),
+            "input: {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_quote_unquote() {
+    let inputs_expected = [
+        ("quote(5)", "QUOTE(5)"),
+        ("quote(5 + 8)", "QUOTE((5 + 8))"),
+        ("quote(foobar)", "QUOTE(foobar)"),
+        ("quote(8 + unquote(2 + 2))", "QUOTE((8 + 4))"),
+        ("quote(unquote(4 + 4) + 8)", "QUOTE((8 + 8))"),
+        ("quote(unquote(true == false))", "QUOTE(false)"),
+        ("quote(unquote(\"a\" + \"b\"))", "QUOTE(ab)"),
+        ("quote(unquote(quote(4 + 4)))", "QUOTE((4 + 4))"),
+        (
+            "let q = quote(4 + 4); quote(unquote(4 + 4) + unquote(q))",
+            "QUOTE((8 + (4 + 4)))",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_unquoted_literals_own_their_tokens() {
+    let inputs_expected = [
+        ("quote(unquote(40 + 2))", "42"),
+        ("quote(unquote(1.5 * 2.0))", "3"),
+        ("quote(unquote(\"a\" + \"\\n\"))", "a\\n"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let mut program = Parser::new(Lexer::new(input)).parse_program().unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        let Object::Quote(expression) = actual else {
+            panic!("expected a quote, got {}", actual);
+        };
+        let (Expression::Integer { token, .. }
+        | Expression::Float { token, .. }
+        | Expression::StringLiteral { token, .. }) = expression.as_ref()
+        else {
+            panic!("expected a literal, got {:?}", expression);
+        };
+        assert!(
+            matches!(token.literal, std::borrow::Cow::Owned(_)),
+            "input: {}",
+            input
+        );
+        assert_eq!(token.literal, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_macro_expansion() {
+    let input = r#"
+        let unless = macro(condition, consequence, alternative) {
+            quote(if (!(unquote(condition))) {
+                unquote(consequence);
+            } else {
+                unquote(alternative);
+            });
+        };
+        unless(10 > 5, puts("not greater"), puts("greater"));
+        "#;
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let mut env = Environment::new();
+    let mut output = Vec::new();
+    let mut ctx = Context::new(&mut output);
+    define_macros(&mut program, &mut env);
+    assert_eq!(program.statements.len(), 1);
+    assert!(matches!(env.get("unless"), Some(Object::Macro { .. })));
+
+    expand_macros(&mut program, &mut env, &mut ctx);
+    assert_eq!(
+        program.statements[0].to_string(),
+        r#"if !(10 > 5) { puts(not greater) } else { puts(greater) }"#
+    );
+
+    let actual =
+        eval_program_with_output(&mut program, &mut env, &mut output).unwrap_or_else(Object::Error);
+
+    assert_eq!(actual, Object::Null);
+    assert_eq!(String::from_utf8(output).unwrap(), "greater\n");
+}
+
+#[test]
+fn test_function_declarations() {
+    let inputs_expected = [
+        ("fn add(x, y) { x + y } add(2, 3);", Object::Int(5)),
+        (
+            "fn fact(n) { if (n < 2) { 1 } else { n * fact(n - 1) } } fact(5);",
+            Object::Int(120),
+        ),
+        ("fn noop() {}; noop();", Object::Null),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_empty_expression_and_undefined_identifiers() {
+    let mut program = Program {
+        statements: vec![Statement::Expression(Expression::None)],
+    };
+    let actual = eval_value(&mut program, &mut Environment::new());
+    assert_eq!(actual, Object::Null);
+
+    let inputs_expected = [
+        ("return;", "nil"),
+        ("let f = fn() { return; }; f()", "nil"),
+        ("let f = fn() { y }; f()", "ERROR: identifier not found: y"),
+        ("let x = 1; x + z", "ERROR: identifier not found: z"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_truthiness() {
+    let inputs_expected = [
+        ("if (0) { 1 } else { 2 }", "1"),
+        ("if (0.0) { 1 } else { 2 }", "1"),
+        ("if (\"\") { 1 } else { 2 }", "1"),
+        ("if ([]) { 1 } else { 2 }", "1"),
+        ("if ({}) { 1 } else { 2 }", "1"),
+        ("if (fn() {}) { 1 } else { 2 }", "1"),
+        ("if (len) { 1 } else { 2 }", "1"),
+        ("if (null) { 1 } else { 2 }", "2"),
+        ("if (false) { 1 } else { 2 }", "2"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_builtin_type() {
+    let inputs_expected = [
+        ("type(5)", Object::Str("INTEGER".into())),
+        ("type(\"a\")", Object::Str("STRING".into())),
+        ("type([1])", Object::Str("ARRAY".into())),
+        ("type({})", Object::Str("HASH".into())),
+        ("type(fn(x) { x })", Object::Str("FUNCTION".into())),
+        ("type(type)", Object::Str("BUILTIN".into())),
+        ("type(null)", Object::Str("NULL".into())),
+        (
+            "type()",
+            Object::Error("wrong number of arguments. got=0, want=1".to_string()),
+        ),
+        (
+            "type(1, 2)",
+            Object::Error("wrong number of arguments. got=2, want=1".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_string_index_expressions() {
+    let inputs_expected = [
+        (r#""hello"[1]"#, "e"),
+        (r#""hello"[0]"#, "h"),
+        (r#"let s = "hello"; s[len(s) - 1]"#, "o"),
+        (r#""héllo"[1]"#, "é"),
+        (r#""日本語"[2]"#, "語"),
+        (r#""hello"[5]"#, "nil"),
+        (r#""hello"[-1]"#, "nil"),
+        (r#"""[0]"#, "nil"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_string_builtins() {
+    let inputs_expected = [
+        (r#"split("a,b,c", ",")"#, "[a, b, c]"),
+        (r#"len(split("a,,c", ","))"#, "3"),
+        (r#"split("a--b", "--")"#, "[a, b]"),
+        (r#"split("abc", ";")"#, "[abc]"),
+        (r#"split("héllo", "")"#, "[h, é, l, l, o]"),
+        (r#"len(split("", ","))"#, "1"),
+        (r#"chars("abc")"#, "[a, b, c]"),
+        (r#"chars("")"#, "[]"),
+        (
+            r#"split("a", 1)"#,
+            "ERROR: arguments to 'split' must be STRING, got INTEGER",
+        ),
+        (
+            r#"split(1, ",")"#,
+            "ERROR: arguments to 'split' must be STRING, got INTEGER",
+        ),
+        (
+            r#"split("a")"#,
+            "ERROR: wrong number of arguments. got=1, want=2",
+        ),
+        (
+            "chars([1])",
+            "ERROR: argument to 'chars' must be STRING, got ARRAY",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_hash_iteration_is_sorted_by_key() {
+    let inputs_expected = [
+        (r#"keys({"b": 1, "c": 2, "a": 3})"#, "[a, b, c]"),
+        (r#"values({"b": 1, "c": 2, "a": 3})"#, "[3, 1, 2]"),
+        (
+            r#"keys({"x": 1, true: 2, 10: 3, false: 4, -5: 5})"#,
+            "[-5, 10, false, true, x]",
+        ),
+        (
+            r#"values({"x": 1, true: 2, 10: 3, false: 4, -5: 5})"#,
+            "[5, 3, 4, 2, 1]",
+        ),
+        (
+            r#"{"x": 1, true: 2, 10: 3, false: 4, -5: 5}"#,
+            r#"{-5: 5, 10: 3, false: 4, true: 2, "x": 1}"#,
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        for _ in 0..5 {
+            let actual = crate::run(input).unwrap();
+            assert_eq!(actual.to_string(), expected, "input: {}", input);
+        }
+    }
+}
+
+#[test]
+fn test_hash_keys_and_values() {
+    let input = r#"
+        let h = {"one": 1, "two": 2, 3: true};
+        [keys(h), values(h), keys({}), values({})]
+        "#;
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut Environment::new());
+
+    let Object::Array(results) = actual else {
+        panic!("expected array object, got {}", actual);
+    };
+    let [
+        Object::Array(keys),
+        Object::Array(values),
+        empty_keys,
+        empty_values,
+    ] = &results[..]
+    else {
+        panic!("unexpected results: {:?}", results);
+    };
+
+    let mut keys = keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, ["3", "one", "two"]);
+
+    let mut values = values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, ["1", "2", "true"]);
+
+    assert_eq!(*empty_keys, Object::Array(vec![].into()));
+    assert_eq!(*empty_values, Object::Array(vec![].into()));
+
+    let inputs_expected = [
+        (
+            "keys([1])",
+            "ERROR: argument to 'keys' must be HASH, got ARRAY",
+        ),
+        (
+            "values(1)",
+            "ERROR: argument to 'values' must be HASH, got INTEGER",
+        ),
+        ("keys()", "ERROR: wrong number of arguments. got=0, want=1"),
+        (
+            "values({}, {})",
+            "ERROR: wrong number of arguments. got=2, want=1",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_string_and_array_equality() {
+    let inputs_expected = [
+        (r#""abc" == "abc""#, "true"),
+        (r#""abc" == "abd""#, "false"),
+        (r#""abc" != "abd""#, "true"),
+        ("[1, 2] == [1, 2]", "true"),
+        ("[1, [2, \"x\"]] == [1, [2, \"x\"]]", "true"),
+        ("[1] != [1, 2]", "true"),
+        ("[1] == [1, 2]", "false"),
+        ("[] == []", "true"),
+        (r#""a" == 1"#, "false"),
+        (r#""a" != 1"#, "true"),
+        ("[1] == 1", "false"),
+        ("true != 1", "true"),
+        (r#""a" < "b""#, "ERROR: unknown operator: STRING < STRING"),
+        ("[1] - [2]", "ERROR: unknown operator: ARRAY - ARRAY"),
+        ("1 < true", "ERROR: type mismatch: INTEGER < BOOLEAN"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_prelude() {
+    let tests = [
+        ("map([1, 2, 3], fn(x) { x * 2 })", "[2, 4, 6]"),
+        ("map([], fn(x) { x })", "[]"),
+        ("reduce([1, 2, 3, 4], 0, fn(acc, x) { acc + x })", "10"),
+        (r#"reduce(["a", "b"], "", fn(acc, x) { acc + x })"#, "ab"),
+        ("filter([1, 2, 3, 4], fn(x) { x % 2 == 0 })", "[2, 4]"),
+        ("let map = fn(x) { x }; map(5)", "5"),
+        ("len(map([1] * 5000, fn(x) { x + 1 }))", "5000"),
+        ("reduce([2] * 5000, 0, fn(acc, x) { acc + x })", "10000"),
+        ("len(filter([1, 2] * 2500, fn(x) { x == 2 }))", "2500"),
+    ];
+
+    for (input, expected) in tests {
+        let actual = crate::run(input).unwrap();
+        assert_eq!(actual.to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_eval_program_separates_errors() {
+    let tests = [
+        ("5 + 5", Ok(Object::Int(10))),
+        ("let x = 1;", Ok(Object::Null)),
+        (
+            "5 + true",
+            Err("type mismatch: INTEGER + BOOLEAN".to_string()),
+        ),
+        ("foo; 5", Err("identifier not found: foo".to_string())),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let mut program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let actual = eval_program(&mut program, &mut Environment::new());
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_power_operator() {
+    let inputs_expected = [
+        ("2 ** 3", "8"),
+        ("2 ** 3 ** 2", "512"),
+        ("(2 ** 3) ** 2", "64"),
+        ("2 * 3 ** 2", "18"),
+        ("-2 ** 2", "4"),
+        ("5 ** 0", "1"),
+        ("2.0 ** 0.5 > 1.41", "true"),
+        ("2 ** 0.5 < 1.42", "true"),
+        ("2 ** 63", "ERROR: integer overflow"),
+        ("2 ** -1", "ERROR: negative exponent: -1"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_method_call_sugar() {
+    let tests = [
+        ("[1, 2, 3].len()", "3"),
+        (r#""a,b".split(",")"#, "[a, b]"),
+        ("[1, 2, 3].map(fn(x) { x * 2 }).rest()", "[4, 6]"),
+        ("let double = fn(x) { x * 2 }; 21.double()", "42"),
+    ];
+
+    for (input, expected) in tests {
+        let actual = crate::run(input).unwrap();
+        assert_eq!(actual.to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_unicode_identifiers_evaluate() {
+    let actual = crate::run("let café = 5; let λ = fn(x) { x * 2 }; λ(café)").unwrap();
+    assert_eq!(actual, Object::Int(10));
+}
+
+#[test]
+fn test_array_concatenation_and_repetition() {
+    let inputs_expected = [
+        ("[1, 2] + [3, 4]", "[1, 2, 3, 4]"),
+        ("[] + [1]", "[1]"),
+        ("let a = [1]; let b = a + [2]; a", "[1]"),
+        ("[0] * 3", "[0, 0, 0]"),
+        ("[1, \"a\"] * 2", "[1, a, 1, a]"),
+        ("[1, 2] * 0", "[]"),
+        ("[1, 2] * -1", "[]"),
+        ("len([0] * 10000000)", "10000000"),
+        (
+            "[1, 2] * 10000000000",
+            "ERROR: array too large: 2 * 10000000000 is more than 10000000 elements",
+        ),
+        (
+            "[1, 2] * 9223372036854775807",
+            "ERROR: array too large: 2 * 9223372036854775807 is more than 10000000 elements",
+        ),
+        ("[] * 10000000000", "[]"),
+        ("[1] * [2]", "ERROR: unknown operator: ARRAY * ARRAY"),
+        ("[1] / 2", "ERROR: type mismatch: ARRAY / INTEGER"),
+        ("3 * [0]", "ERROR: type mismatch: INTEGER * ARRAY"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_run_timed() {
+    let (result, timings) = crate::run_timed(
+        "let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }; fib(15)",
+    );
+
+    assert_eq!(result, Ok(Object::Int(610)));
+    assert!(timings.parse > std::time::Duration::ZERO);
+    assert!(timings.eval > std::time::Duration::ZERO);
+
+    let (result, timings) = crate::run_timed("let x 5;");
+    assert!(matches!(result, Err(crate::RunError::Parse(_))));
+    assert_eq!(timings.eval, std::time::Duration::ZERO);
+}
+
+#[test]
+fn test_top_level_return_is_unwrapped() {
+    let tests = [
+        ("return 5; 10;", Object::Int(5)),
+        ("return;", Object::Null),
+        ("return; 10;", Object::Null),
+        (
+            "let x = 1; return x + 1; puts(\"unreachable\");",
+            Object::Int(2),
+        ),
+        (
+            "if (true) { return [1]; } 10",
+            Object::Array(vec![Object::Int(1)].into()),
+        ),
+        ("let f = fn() { return 5; }; f()", Object::Int(5)),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let mut program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut output = Vec::new();
+        let actual = eval_program_with_output(&mut program, &mut Environment::new(), &mut output);
+        assert_eq!(actual, Ok(expected), "input: {}", input);
+        assert!(output.is_empty(), "input: {}", input);
+    }
+}
+
+#[test]
+fn test_negative_integers() {
+    let inputs_expected = [
+        ("let x = -5; -x;", "5"),
+        ("--5", "5"),
+        ("-(-(5))", "5"),
+        ("- - -5", "-5"),
+        ("-5 * -2", "10"),
+        ("2 - -3", "5"),
+        ("-2 + 3", "1"),
+        ("-(2 + 3)", "-5"),
+        ("-9223372036854775807", "-9223372036854775807"),
+        ("-9223372036854775808", "-9223372036854775808"),
+        ("-9223372036854775808 + 1", "-9223372036854775807"),
+        ("-9223372036854775808 - 1", "ERROR: integer overflow"),
+        ("--9223372036854775808", "ERROR: integer overflow"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_eval_to_string() {
+    let tests = [
+        ("let add = fn(a, b) { a + b }; add(2, 3)", "5"),
+        ("puts(\"hi\"); [1, 2].map(fn(x) { x * 2 })", "hi\n[2, 4]"),
+        ("let x = 1;", "nil"),
+        ("5 + true", "ERROR: type mismatch: INTEGER + BOOLEAN"),
+        (
+            "let x 5; let = 1;",
+            "expected next token to be Assign, got Int instead at line 1, col 7\nexpected next token to be Ident, got Assign instead at line 1, col 14",
+        ),
+        ("\"open", "unterminated string literal at line 1, col 1"),
+        ("", "nil"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(crate::eval_to_string(input), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_eval_to_string_deeply_nested() {
+    let tests = [
+        (
+            format!("{}1{}", "(".repeat(50_000), ")".repeat(50_000)),
+            "expression nested more than 1000 levels deep at line 1, col 1001",
+        ),
+        (
+            format!("{}1", "- ".repeat(50_000)),
+            "expression nested more than 1000 levels deep at line 1, col 2001",
+        ),
+        (
+            format!("let x = {}1", "[".repeat(50_000)),
+            "expression nested more than 1000 levels deep at line 1, col 1009",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let actual = std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(move || crate::eval_to_string(&input))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn test_hash_display_is_sorted() {
+    let tests = [
+        (r#"{"two": 2, "one": 1}"#, r#"{"one": 1, "two": 2}"#),
+        (
+            r#"{"b": [1, 2], true: "yes", 10: null, "a": {2: 2, 1: 1}, false: 0, -1: 1.5}"#,
+            r#"{-1: 1.5, 10: nil, false: 0, true: yes, "a": {1: 1, 2: 2}, "b": [1, 2]}"#,
+        ),
+        (r#"{"say \"hi\"": 1}"#, r#"{"say \"hi\"": 1}"#),
+        ("{}", "{}"),
+    ];
+
+    for (input, expected) in tests {
+        for _ in 0..5 {
+            let actual = crate::run(input).unwrap();
+            assert_eq!(actual.to_string(), expected, "input: {}", input);
+        }
+    }
+}
+
+#[test]
+fn test_mixed_numeric_comparisons() {
+    let inputs_expected = [
+        ("1 < 2.5", "true"),
+        ("2.5 < 1", "false"),
+        ("3 > 2.5", "true"),
+        ("2.5 > 3", "false"),
+        ("3 <= 3.0", "true"),
+        ("3.5 <= 3", "false"),
+        ("3.0 >= 3", "true"),
+        ("2 >= 2.5", "false"),
+        ("3.0 == 3", "true"),
+        ("3 == 3.0", "true"),
+        ("3 == 3.5", "false"),
+        ("3.5 != 3", "true"),
+        ("3 != 3.0", "false"),
+        ("1 <= 1", "true"),
+        ("2 >= 3", "false"),
+        ("1 + 1 <= 2 == true", "true"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_builtin_format() {
+    let inputs_expected = [
+        (
+            r#"format("{} + {} = {}", 1, 2, 3)"#,
+            Object::Str("1 + 2 = 3".into()),
+        ),
+        (
+            r#"format("no placeholders")"#,
+            Object::Str("no placeholders".into()),
+        ),
+        (
+            r#"format("{}: {}", "list", [1, "a"])"#,
+            Object::Str("list: [1, a]".into()),
+        ),
+        (r#"format("{{}} {}", true)"#, Object::Str("{} true".into())),
+        (r#"format("{{{}}}", 5)"#, Object::Str("{5}".into())),
+        (r#"format("{}{}", null, 1.5)"#, Object::Str("nil1.5".into())),
+        (
+            r#"format("{} and {}", 1)"#,
+            Object::Error(
+                "format string has 2 placeholders but 1 arguments were given".to_string(),
+            ),
+        ),
+        (
+            r#"format("{}", 1, 2)"#,
+            Object::Error(
+                "format string has 1 placeholders but 2 arguments were given".to_string(),
+            ),
+        ),
+        (
+            r#"format("{ }", 1)"#,
+            Object::Error("unmatched '{' in format string".to_string()),
+        ),
+        (
+            r#"format("}", 1)"#,
+            Object::Error("unmatched '}' in format string".to_string()),
+        ),
+        (
+            "format()",
+            Object::Error("wrong number of arguments. got=0, want at least 1".to_string()),
+        ),
+        (
+            "format(1)",
+            Object::Error("first argument to 'format' must be STRING, got INTEGER".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_empty_blocks_and_programs_evaluate_to_null() {
+    let inputs = [
+        "",
+        "if (true) {}",
+        "if (false) { 1 } else {}",
+        "fn() {}()",
+        "let f = fn() {}; f()",
+    ];
+
+    for input in inputs {
+        let mut parser = Parser::new(Lexer::new(input));
+        let mut program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors.is_empty(), "input: {:?}", input);
+
+        let actual = eval_program(&mut program, &mut Environment::new());
+        assert_eq!(actual, Ok(Object::Null), "input: {:?}", input);
+    }
+}
+
+#[test]
+fn test_math_builtins() {
+    let inputs_expected = [
+        ("sqrt(9.0)", Object::Float(3.0)),
+        ("sqrt(16)", Object::Float(4.0)),
+        ("sqrt(2.25)", Object::Float(1.5)),
+        ("sqrt(0)", Object::Float(0.0)),
+        (
+            "sqrt(-1.0)",
+            Object::Error("cannot take sqrt of negative number -1".to_string()),
+        ),
+        (
+            "sqrt(-4)",
+            Object::Error("cannot take sqrt of negative number -4".to_string()),
+        ),
+        ("floor(3.7)", Object::Int(3)),
+        ("floor(-3.2)", Object::Int(-4)),
+        ("floor(7)", Object::Int(7)),
+        ("ceil(3.2)", Object::Int(4)),
+        ("ceil(-3.7)", Object::Int(-3)),
+        ("ceil(7)", Object::Int(7)),
+        ("[1, 2, 3][floor(1.9)]", Object::Int(2)),
+        (
+            "ceil(10.0 ** 30)",
+            Object::Error(
+                "'ceil' result out of integer range: 1000000000000000000000000000000".to_string(),
+            ),
+        ),
+        ("abs(-5)", Object::Int(5)),
+        ("abs(5)", Object::Int(5)),
+        ("abs(-2.5)", Object::Float(2.5)),
+        (
+            "abs(-9223372036854775808)",
+            Object::Error("integer overflow".to_string()),
+        ),
+        (
+            "abs(\"a\")",
+            Object::Error("argument to 'abs' must be INTEGER or FLOAT, got STRING".to_string()),
+        ),
+        (
+            "floor(true)",
+            Object::Error("argument to 'floor' must be INTEGER or FLOAT, got BOOLEAN".to_string()),
+        ),
+        (
+            "sqrt(1, 2)",
+            Object::Error("wrong number of arguments. got=2, want=1".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let mut program = parser.parse_program().unwrap();
+        assert!(parser.errors.is_empty());
+
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_left_associative_evaluation() {
+    let inputs_expected = [
+        ("10 - 5 - 2", "3"),
+        ("100 / 5 / 2", "10"),
+        ("100 % 7 % 3", "2"),
+        ("10 - 5 + 2", "7"),
+        ("10 / 5 * 2", "4"),
+        ("20.0 / 4.0 / 2.0", "2.5"),
+        ("1 - 2 - 3 - 4", "-8"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_bare_block_scope() {
+    let inputs_expected = [
+        ("{ let x = 5; x; }", "5"),
+        ("{ let x = 5; x * 2 }", "10"),
+        ("{ let x = 5; } x", "ERROR: identifier not found: x"),
+        ("let x = 1; { let x = 2; } x", "1"),
+        ("let x = 1; { x = 2; } x", "2"),
+        ("let x = 1; { let y = x + 1; { let z = y + 1; z } }", "3"),
+        ("let f = fn() { { return 5; } 10 }; f()", "5"),
+        ("{}", "{}"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_bang_operator_on_every_type() {
+    let inputs_expected = [
+        ("!true", "false"),
+        ("!false", "true"),
+        ("!null", "true"),
+        ("!!null", "false"),
+        ("!0", "false"),
+        ("!5", "false"),
+        ("!1.5", "false"),
+        ("!\"x\"", "false"),
+        ("!\"\"", "false"),
+        ("![1]", "false"),
+        ("![]", "false"),
+        ("!{}", "false"),
+        ("!{\"a\": 1}", "false"),
+        ("!fn(x) { x }", "false"),
+        ("!len", "false"),
+        ("!quote(1)", "false"),
+        ("!![1]", "true"),
+        ("!(1 + true)", "ERROR: type mismatch: INTEGER + BOOLEAN"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_bitwise_operators() {
+    let inputs_expected = [
+        ("12 & 10", "8"),
+        ("12 | 10", "14"),
+        ("12 ^ 10", "6"),
+        ("1 << 4", "16"),
+        ("256 >> 4", "16"),
+        ("-16 >> 2", "-4"),
+        ("-1 & 255", "255"),
+        ("1 << 63", "-9223372036854775808"),
+        ("1 | 2 ^ 6 & 3", "1"),
+        ("1 << 2 + 1", "8"),
+        ("(5 & 3) == 1", "true"),
+        ("1 << -1", "ERROR: invalid shift amount: -1"),
+        ("1 >> 64", "ERROR: invalid shift amount: 64"),
+        ("1 << 9999999999", "ERROR: invalid shift amount: 9999999999"),
+        ("1.5 & 1", "ERROR: unknown operator: FLOAT & FLOAT"),
+        ("true | false", "ERROR: unknown operator: BOOLEAN | BOOLEAN"),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_ternary_expression() {
+    let inputs_expected = [
+        (r#"let x = 5; x > 0 ? "pos" : "neg""#, "pos"),
+        (r#"let x = -5; x > 0 ? "pos" : "neg""#, "neg"),
+        ("null ? 1 : 2", "2"),
+        ("0 ? 1 : 2", "1"),
+        ("let n = 0; n < 0 ? -1 : n == 0 ? 0 : 1", "0"),
+        ("let f = fn(n) { n > 1 ? n * f(n - 1) : 1 }; f(5)", "120"),
+        ("true ? 1 : 1 / 0", "1"),
+        ("false ? 1 / 0 : 2", "2"),
+        (
+            "(1 + true) ? 1 : 2",
+            "ERROR: type mismatch: INTEGER + BOOLEAN",
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(format!("{}", actual), expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_assert_builtins() {
+    let inputs_expected = [
+        ("assert(true)", Object::Null),
+        ("assert(1 < 2)", Object::Null),
+        ("assert(0)", Object::Null),
+        (
+            "assert(false)",
+            Object::Error("assertion failed: false".to_string()),
+        ),
+        (
+            "assert(null)",
+            Object::Error("assertion failed: nil".to_string()),
+        ),
+        ("assert_eq(1 + 1, 2)", Object::Null),
+        (r#"assert_eq("a", "a")"#, Object::Null),
+        ("assert_eq([1, [2]], [1, [2]])", Object::Null),
+        (
+            "assert_eq(1 + 1, 3)",
+            Object::Error("assertion failed: 2 != 3".to_string()),
+        ),
+        (
+            "assert_eq([1, 2], [2, 1])",
+            Object::Error("assertion failed: [1, 2] != [2, 1]".to_string()),
+        ),
+        (
+            "assert_eq(1, true)",
+            Object::Error("assertion failed: 1 != true".to_string()),
+        ),
+        (
+            "assert_eq(1, 2); 3",
+            Object::Error("assertion failed: 1 != 2".to_string()),
+        ),
+        (
+            "assert()",
+            Object::Error("wrong number of arguments. got=0, want=1".to_string()),
+        ),
+        (
+            "assert_eq(1)",
+            Object::Error("wrong number of arguments. got=1, want=2".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut Environment::new());
+
+        assert_eq!(actual, expected, "input: {}", input)
+    }
+}
+
+#[test]
+fn test_register_builtin() {
+    let mut env = Environment::new();
+    env.register_builtin("double", |args, _| match args.as_slice() {
+        [Object::Int(i)] => Object::Int(i * 2),
+        _ => Object::Error("double expects one INTEGER".to_string()),
+    });
+    env.register_builtin("len", |_, _| Object::Int(-1));
+
+    let inputs_expected = [
+        ("double(21)", Object::Int(42)),
+        (
+            "let f = fn(x) { double(x) + 1 }; f(double(2))",
+            Object::Int(9),
+        ),
+        ("type(double)", Object::Str("BUILTIN".into())),
+        ("len([1, 2])", Object::Int(-1)),
+        (
+            "double(true)",
+            Object::Error("double expects one INTEGER".to_string()),
+        ),
+    ];
+
+    for (input, expected) in inputs_expected {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert!(program.is_some());
+        assert!(parser.errors.is_empty(), "input: {}", input);
+
+        let mut program = program.unwrap();
+        let actual = eval_value(&mut program, &mut env.clone());
+
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_register_capturing_builtin() {
+    let offset = 10;
+    let calls = std::cell::Cell::new(0);
+
+    let mut env = Environment::new();
+    env.register_builtin("offset", |args, _| {
+        calls.set(calls.get() + 1);
+        match args.as_slice() {
+            [Object::Int(i)] => Object::Int(i + offset),
+            _ => Object::Error("offset expects one INTEGER".to_string()),
+        }
+    });
+
+    let lexer = Lexer::new("let f = fn(x) { offset(x) }; f(1) + offset(2)");
+    let mut parser = Parser::new(lexer);
+    let mut program = parser.parse_program().unwrap();
+    assert!(parser.errors.is_empty());
+
+    let actual = eval_value(&mut program, &mut env);
+
+    assert_eq!(actual, Object::Int(23));
+    assert_eq!(calls.get(), 2);
+}
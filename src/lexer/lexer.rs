@@ -1,37 +1,70 @@
-use std::{iter::Peekable, str::Chars};
+use std::{iter::Peekable, ops::Range, str::Chars};
 
-use super::{Token, TokenType};
+use super::{Token, TokenType, token::KEYWORDS};
 
 #[derive(Debug, Clone)]
 pub struct Lexer<'input> {
     input: &'input str,
     peekable: Peekable<Chars<'input>>,
     position: usize,
+    // Byte offset where the most recently returned token starts
+    token_start: usize,
+    line: usize,
+    column: usize,
+    // Emit comments as `Comment` tokens instead of skipping them
+    preserve_comments: bool,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
-        let lexer = Lexer {
+        Lexer {
             input,
             position: 0,
+            token_start: 0,
             peekable: input.chars().peekable(),
-        };
+            line: 1,
+            column: 1,
+            preserve_comments: false,
+        }
+    }
 
-        lexer
+    /// Makes the lexer return comments as `Comment` tokens, whose literal is
+    /// the whole comment including its delimiters, for tools such as
+    /// formatters. The parser skips them either way.
+    pub fn preserve_comments(self, preserve_comments: bool) -> Self {
+        Self {
+            preserve_comments,
+            ..self
+        }
     }
 
     pub fn next_char(&mut self) -> Option<char> {
         match self.peekable.next() {
             Some(x) => {
                 self.position += x.len_utf8();
+                if x == '\n' {
+                    self.line += 1;
+                    self.column = 1;
+                } else {
+                    self.column += 1;
+                }
                 Some(x)
             }
             None => None,
         }
     }
 
+    /// Byte range in the input covered by the most recently returned token.
+    pub fn span(&self) -> Range<usize> {
+        self.token_start..self.position
+    }
+
     pub fn next_token(&mut self) -> Token<'a> {
-        self.skip_whitespace();
+        if let Some(token) = self.skip_whitespace_and_comments() {
+            return token;
+        }
+        let (line, column) = (self.line, self.column);
+        self.token_start = self.position;
         let token = match self.next_char() {
             Some('=') => {
                 if self.peekable.peek() == Some(&'=') {
@@ -43,10 +76,32 @@ impl<'a> Lexer<'a> {
             }
             Some('{') => Token::new(TokenType::LBrace, "{"),
             Some('}') => Token::new(TokenType::RBrace, "}"),
+            Some('[') => Token::new(TokenType::LBracket, "["),
+            Some(']') => Token::new(TokenType::RBracket, "]"),
             Some('+') => Token::new(TokenType::Plus, "+"),
             Some('-') => Token::new(TokenType::Minus, "-"),
-            Some('<') => Token::new(TokenType::Lt, "<"),
-            Some('>') => Token::new(TokenType::Gt, ">"),
+            Some('<') => match self.peekable.peek() {
+                Some('=') => {
+                    self.next_char();
+                    Token::new(TokenType::LtEq, "<=")
+                }
+                Some('<') => {
+                    self.next_char();
+                    Token::new(TokenType::ShiftLeft, "<<")
+                }
+                _ => Token::new(TokenType::Lt, "<"),
+            },
+            Some('>') => match self.peekable.peek() {
+                Some('=') => {
+                    self.next_char();
+                    Token::new(TokenType::GtEq, ">=")
+                }
+                Some('>') => {
+                    self.next_char();
+                    Token::new(TokenType::ShiftRight, ">>")
+                }
+                _ => Token::new(TokenType::Gt, ">"),
+            },
             Some('!') => {
                 if self.peekable.peek() == Some(&'=') {
                     self.next_char();
@@ -55,25 +110,43 @@ impl<'a> Lexer<'a> {
                     Token::new(TokenType::Bang, "!")
                 }
             }
-            Some('*') => Token::new(TokenType::Asterisk, "*"),
+            Some('*') => {
+                if self.peekable.peek() == Some(&'*') {
+                    self.next_char();
+                    Token::new(TokenType::Power, "**")
+                } else {
+                    Token::new(TokenType::Asterisk, "*")
+                }
+            }
             Some('/') => Token::new(TokenType::Slash, "/"),
+            Some('%') => Token::new(TokenType::Percent, "%"),
+            Some('&') => Token::new(TokenType::Ampersand, "&"),
+            Some('|') => Token::new(TokenType::Pipe, "|"),
+            Some('^') => Token::new(TokenType::Caret, "^"),
             Some(',') => Token::new(TokenType::Comma, ","),
+            Some('.') => Token::new(TokenType::Dot, "."),
             Some(';') => Token::new(TokenType::Semicolon, ";"),
+            Some(':') => Token::new(TokenType::Colon, ":"),
+            Some('?') => Token::new(TokenType::Question, "?"),
             Some('(') => Token::new(TokenType::LParen, "("),
             Some(')') => Token::new(TokenType::RParen, ")"),
+            Some('"') => match self.read_string() {
+                Ok(literal) => Token::new(TokenType::String, literal),
+                Err(message) => Token::new(TokenType::Illegal, message),
+            },
             Some(c) if c.is_alphabetic() || c == '_' => {
                 let literal = self.read_identifier(c);
                 let tok_type = self.lookup_identifier(literal);
                 Token::new(tok_type, literal)
             }
             Some(c) if c.is_ascii_digit() => {
-                let literal = self.read_number(c);
-                Token::new(TokenType::Int, literal)
+                let (literal, tok_type) = self.read_number(c);
+                Token::new(tok_type, literal)
             }
             Some(_) => Token::new(TokenType::Illegal, "Illegal"),
             None => Token::new(TokenType::Eof, ""),
         };
-        token
+        token.at(line, column)
     }
 
     fn read_identifier(&mut self, first: char) -> &'a str {
@@ -88,16 +161,75 @@ impl<'a> Lexer<'a> {
         &self.input[start..self.position]
     }
 
-    fn read_number(&mut self, first: char) -> &'a str {
+    fn read_number(&mut self, first: char) -> (&'a str, TokenType) {
         let start = self.position - first.len_utf8();
+        self.read_digits();
+
+        // Only treat the dot as a decimal point when a digit follows it
+        let mut rest = self.input[self.position..].chars();
+        if rest.next() == Some('.') && rest.next().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.next_char();
+            self.read_digits();
+            return (&self.input[start..self.position], TokenType::Float);
+        }
+
+        (&self.input[start..self.position], TokenType::Int)
+    }
+
+    fn read_digits(&mut self) {
         while let Some(&ch) = self.peekable.peek() {
-            if ch.is_numeric() {
+            if ch.is_ascii_digit() {
                 self.next_char();
             } else {
                 break;
             }
         }
-        &self.input[start..self.position]
+    }
+
+    // The literal keeps escapes verbatim; use `unescape` to get the string's value
+    fn read_string(&mut self) -> Result<&'a str, &'static str> {
+        let start = self.position;
+        let mut invalid_escape = false;
+
+        while let Some(ch) = self.next_char() {
+            match ch {
+                '"' if invalid_escape => return Err("invalid escape sequence in string literal"),
+                '"' => return Ok(&self.input[start..self.position - 1]),
+                '\\' => invalid_escape |= !matches!(self.next_char(), Some('n' | 't' | '"' | '\\')),
+                _ => {}
+            }
+        }
+        Err("unterminated string literal")
+    }
+
+    // Returns an Illegal token if a block comment is left unterminated, or the
+    // comment itself when comments are preserved
+    fn skip_whitespace_and_comments(&mut self) -> Option<Token<'a>> {
+        loop {
+            self.skip_whitespace();
+
+            let (line, column) = (self.line, self.column);
+            let rest = &self.input[self.position..];
+            if rest.starts_with("//") {
+                self.token_start = self.position;
+                self.skip_line_comment();
+            } else if rest.starts_with("/*") {
+                self.token_start = self.position;
+                if !self.skip_block_comment() {
+                    return Some(
+                        Token::new(TokenType::Illegal, "unterminated block comment")
+                            .at(line, column),
+                    );
+                }
+            } else {
+                return None;
+            }
+
+            if self.preserve_comments {
+                let literal = &self.input[self.token_start..self.position];
+                return Some(Token::new(TokenType::Comment, literal).at(line, column));
+            }
+        }
     }
 
     fn skip_whitespace(&mut self) {
@@ -110,16 +242,130 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    fn skip_block_comment(&mut self) -> bool {
+        let mut depth = 0;
+
+        loop {
+            let rest = &self.input[self.position..];
+            if rest.starts_with("/*") {
+                depth += 1;
+                self.next_char();
+                self.next_char();
+            } else if rest.starts_with("*/") {
+                depth -= 1;
+                self.next_char();
+                self.next_char();
+                if depth == 0 {
+                    return true;
+                }
+            } else if self.next_char().is_none() {
+                return false;
+            }
+        }
+    }
+
+    fn skip_line_comment(&mut self) {
+        while let Some(&ch) = self.peekable.peek() {
+            if ch == '\n' {
+                break;
+            }
+            self.next_char();
+        }
+    }
+
     fn lookup_identifier(&self, ident: &str) -> TokenType {
-        match ident {
-            "fn" => TokenType::Function,
-            "let" => TokenType::Let,
-            "true" => TokenType::True,
-            "false" => TokenType::False,
-            "else" => TokenType::Else,
-            "if" => TokenType::If,
-            "return" => TokenType::Return,
-            _ => TokenType::Ident,
+        KEYWORDS
+            .iter()
+            .find(|(keyword, _)| *keyword == ident)
+            .map_or(TokenType::Ident, |(_, token_type)| *token_type)
+    }
+}
+
+/// Iterator over the tokens of a [`Lexer`], ending with (and including) `Eof`.
+#[derive(Debug, Clone)]
+pub struct TokenStream<'a> {
+    lexer: Lexer<'a>,
+    finished: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn tokens(self) -> TokenStream<'a> {
+        TokenStream {
+            lexer: self,
+            finished: false,
         }
     }
 }
+
+impl<'a> Iterator for TokenStream<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let token = self.lexer.next_token();
+        self.finished = token.token_type == TokenType::Eof;
+        Some(token)
+    }
+}
+
+/// Resolves the escape sequences in a string literal accepted by the lexer.
+pub fn unescape(literal: &str) -> String {
+    let mut result = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+
+    result
+}
+
+/// Lexes `input` to completion, returning every token (without the final
+/// `Eof`) together with its byte span.
+///
+/// Stops at the first illegal token and returns its message and location.
+pub fn tokenize(input: &str) -> Result<Vec<(Token<'_>, Range<usize>)>, String> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token();
+        match token.token_type {
+            TokenType::Eof => return Ok(tokens),
+            TokenType::Illegal => {
+                return Err(format!(
+                    "{} at line {}, col {}",
+                    token.literal, token.line, token.column
+                ));
+            }
+            _ => tokens.push((token, lexer.span())),
+        }
+    }
+}
+
+/// Renders the token stream of `input` one token per line as
+/// `TYPE LITERAL START..END`, for debugging the lexer.
+pub fn dump_tokens(input: &str) -> Result<String, String> {
+    let tokens = tokenize(input)?;
+
+    Ok(tokens
+        .iter()
+        .map(|(token, span)| {
+            format!(
+                "{:?} {:?} {}..{}\n",
+                token.token_type, token.literal, span.start, span.end
+            )
+        })
+        .collect())
+}
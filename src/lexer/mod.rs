@@ -2,7 +2,7 @@
 mod tests;
 
 pub mod lexer;
-pub use lexer::Lexer;
+pub use lexer::{Lexer, TokenStream, dump_tokens, tokenize};
 pub mod precedence;
 pub use precedence::Precedence;
 pub mod token;
@@ -1,10 +1,82 @@
+use super::token::TokenType;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum Precedence {
     Lowest = 1,
+    Ternary,
     Equals,
     LessGreater,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
     Sum,
     Product,
+    Power,
     Prefix,
     Call,
+    Index,
+}
+
+/// Binding power of every infix-position token. Tokens not listed bind at
+/// `Precedence::Lowest`.
+pub const PRECEDENCES: &[(TokenType, Precedence)] = &[
+    (TokenType::Question, Precedence::Ternary),
+    (TokenType::Eq, Precedence::Equals),
+    (TokenType::NotEq, Precedence::Equals),
+    (TokenType::Lt, Precedence::LessGreater),
+    (TokenType::Gt, Precedence::LessGreater),
+    (TokenType::LtEq, Precedence::LessGreater),
+    (TokenType::GtEq, Precedence::LessGreater),
+    (TokenType::Pipe, Precedence::BitOr),
+    (TokenType::Caret, Precedence::BitXor),
+    (TokenType::Ampersand, Precedence::BitAnd),
+    (TokenType::ShiftLeft, Precedence::Shift),
+    (TokenType::ShiftRight, Precedence::Shift),
+    (TokenType::Plus, Precedence::Sum),
+    (TokenType::Minus, Precedence::Sum),
+    (TokenType::Asterisk, Precedence::Product),
+    (TokenType::Slash, Precedence::Product),
+    (TokenType::Percent, Precedence::Product),
+    (TokenType::Power, Precedence::Power),
+    (TokenType::LParen, Precedence::Call),
+    (TokenType::Dot, Precedence::Call),
+    (TokenType::LBracket, Precedence::Index),
+];
+
+/// Infix operators that group to the right, so `a ** b ** c` is
+/// `a ** (b ** c)` and `a ? b : c ? d : e` is `a ? b : (c ? d : e)`. Every
+/// other operator groups to the left.
+pub const RIGHT_ASSOCIATIVE: &[TokenType] = &[TokenType::Power, TokenType::Question];
+
+impl Precedence {
+    pub fn of(token_type: TokenType) -> Precedence {
+        PRECEDENCES
+            .iter()
+            .find(|(t, _)| *t == token_type)
+            .map_or(Precedence::Lowest, |(_, p)| *p)
+    }
+
+    pub fn is_right_associative(token_type: TokenType) -> bool {
+        RIGHT_ASSOCIATIVE.contains(&token_type)
+    }
+
+    /// The tier just below this one (`Lowest` stays `Lowest`).
+    pub fn lower(self) -> Precedence {
+        match self {
+            Precedence::Lowest | Precedence::Ternary => Precedence::Lowest,
+            Precedence::Equals => Precedence::Ternary,
+            Precedence::LessGreater => Precedence::Equals,
+            Precedence::BitOr => Precedence::LessGreater,
+            Precedence::BitXor => Precedence::BitOr,
+            Precedence::BitAnd => Precedence::BitXor,
+            Precedence::Shift => Precedence::BitAnd,
+            Precedence::Sum => Precedence::Shift,
+            Precedence::Product => Precedence::Sum,
+            Precedence::Power => Precedence::Product,
+            Precedence::Prefix => Precedence::Power,
+            Precedence::Call => Precedence::Prefix,
+            Precedence::Index => Precedence::Call,
+        }
+    }
 }
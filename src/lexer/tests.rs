@@ -1,4 +1,18 @@
-use super::{Lexer, TokenType};
+use super::{
+    Lexer, Precedence, TokenType, dump_tokens,
+    lexer::unescape,
+    precedence::PRECEDENCES,
+    token::{KEYWORDS, Token},
+    tokenize,
+};
+
+// The literal of a lexed token, which always borrows from the input
+fn literal(token: Token<'_>) -> &str {
+    match token.literal {
+        std::borrow::Cow::Borrowed(literal) => literal,
+        std::borrow::Cow::Owned(_) => panic!("lexed token owns its literal"),
+    }
+}
 
 #[test]
 fn test_next_token() {
@@ -8,7 +22,7 @@ let add = fn(x, y) {
 x + y;
 };
 let result = add(five, ten);
-!-/*5;
+!-/ *5;
 5 < 10 > 5;
 if (5 < 10) {
 return true;
@@ -115,3 +129,742 @@ return true;
         );
     }
 }
+
+#[test]
+fn test_string_token() {
+    let input = r#""foobar" "hello world";"#;
+    let tests = [
+        (TokenType::String, "foobar"),
+        (TokenType::String, "hello world"),
+        (TokenType::Semicolon, ";"),
+        (TokenType::Eof, ""),
+    ];
+
+    let mut lexer = Lexer::new(input);
+
+    for (i, (expected_type, expected_literal)) in tests.iter().enumerate() {
+        let token = lexer.next_token();
+        assert_eq!(
+            &token.token_type, expected_type,
+            "tests[{}] - token type wrong. expected={:?}, got={:?}",
+            i, expected_type, token
+        );
+        assert_eq!(
+            &token.literal, expected_literal,
+            "tests[{}] - literal wrong. expected={}, got={}",
+            i, expected_literal, token.literal
+        );
+    }
+}
+
+#[test]
+fn test_percent_token() {
+    let mut lexer = Lexer::new("10 % 3");
+
+    assert_eq!(lexer.next_token().token_type, TokenType::Int);
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Percent);
+    assert_eq!(token.literal, "%");
+    assert_eq!(lexer.next_token().token_type, TokenType::Int);
+    assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+}
+
+#[test]
+fn test_token_positions() {
+    let input = "let x = 5;\n  let yy = x;";
+    let expected = [
+        (TokenType::Let, 1, 1),
+        (TokenType::Ident, 1, 5),
+        (TokenType::Assign, 1, 7),
+        (TokenType::Int, 1, 9),
+        (TokenType::Semicolon, 1, 10),
+        (TokenType::Let, 2, 3),
+        (TokenType::Ident, 2, 7),
+        (TokenType::Assign, 2, 10),
+        (TokenType::Ident, 2, 12),
+        (TokenType::Semicolon, 2, 13),
+        (TokenType::Eof, 2, 14),
+    ];
+
+    let mut lexer = Lexer::new(input);
+
+    for (expected_type, line, column) in expected {
+        let token = lexer.next_token();
+        assert_eq!(token.token_type, expected_type);
+        assert_eq!(
+            (token.line, token.column),
+            (line, column),
+            "wrong position for {:?}",
+            token
+        );
+    }
+}
+
+#[test]
+fn test_large_input() {
+    let input = "let value = add(five, 10) * 2;\n".repeat(2000);
+
+    let mut lexer = Lexer::new(&input);
+    let mut count = 0;
+    let mut last = lexer.next_token();
+    while last.token_type != TokenType::Eof {
+        if count % 12 == 0 {
+            assert_eq!(last.token_type, TokenType::Let, "token {}", count);
+            assert_eq!(last.line, count / 12 + 1);
+        }
+        count += 1;
+        last = lexer.next_token();
+    }
+
+    assert_eq!(count, 12 * 2000);
+    assert_eq!(last.line, 2001);
+}
+
+#[test]
+fn test_line_comments() {
+    let with_comments = "// leading comment\nlet x = 5; // this is x\n x; // trailing";
+    let without_comments = "let x = 5;\n x;";
+
+    let mut commented = Lexer::new(with_comments);
+    let mut plain = Lexer::new(without_comments);
+
+    loop {
+        let expected = plain.next_token();
+        let actual = commented.next_token();
+        assert_eq!(actual.token_type, expected.token_type);
+        assert_eq!(actual.literal, expected.literal);
+        if expected.token_type == TokenType::Eof {
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_line_comment_positions() {
+    let mut lexer = Lexer::new("// comment\n  x / y");
+
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Ident);
+    assert_eq!((token.line, token.column), (2, 3));
+
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Slash);
+    assert_eq!((token.line, token.column), (2, 5));
+}
+
+#[test]
+fn test_block_comments() {
+    let inputs = [
+        "/* simple */ let x = 5;",
+        "let /* inline */ x = 5;",
+        "/* outer /* inner */ still comment */ let x = 5;",
+        "/* multi\n line\n comment */\nlet x = 5; /**/",
+    ];
+
+    for input in inputs {
+        let mut lexer = Lexer::new(input);
+        let expected = [
+            TokenType::Let,
+            TokenType::Ident,
+            TokenType::Assign,
+            TokenType::Int,
+            TokenType::Semicolon,
+            TokenType::Eof,
+        ];
+        for expected_type in expected {
+            assert_eq!(
+                lexer.next_token().token_type,
+                expected_type,
+                "input: {}",
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn test_unterminated_block_comment() {
+    let inputs = ["let x = 5; /* never closed", "/* outer /* inner */ x"];
+
+    for input in inputs {
+        let mut lexer = Lexer::new(input);
+        let mut token = lexer.next_token();
+        while token.token_type != TokenType::Illegal && token.token_type != TokenType::Eof {
+            token = lexer.next_token();
+        }
+
+        assert_eq!(token.token_type, TokenType::Illegal, "input: {}", input);
+        assert_eq!(token.literal, "unterminated block comment");
+        assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+    }
+}
+
+#[test]
+fn test_float_token() {
+    let input = "3.14 10 0.5 1.x";
+    let tests = [
+        (TokenType::Float, "3.14"),
+        (TokenType::Int, "10"),
+        (TokenType::Float, "0.5"),
+        (TokenType::Int, "1"),
+        (TokenType::Dot, "."),
+        (TokenType::Ident, "x"),
+        (TokenType::Eof, ""),
+    ];
+
+    let mut lexer = Lexer::new(input);
+
+    for (i, (expected_type, expected_literal)) in tests.iter().enumerate() {
+        let token = lexer.next_token();
+        assert_eq!(
+            &token.token_type, expected_type,
+            "tests[{}] - token type wrong. expected={:?}, got={:?}",
+            i, expected_type, token
+        );
+        assert_eq!(
+            &token.literal, expected_literal,
+            "tests[{}] - literal wrong. expected={}, got={}",
+            i, expected_literal, token.literal
+        );
+    }
+}
+
+#[test]
+fn test_string_escape_sequences() {
+    let tests = [
+        (r#""line1\nline2""#, "line1\nline2"),
+        (r#""a\tb""#, "a\tb"),
+        (r#""say \"hi\"""#, "say \"hi\""),
+        (r#""back\\slash""#, "back\\slash"),
+        (r#""plain""#, "plain"),
+    ];
+
+    for (input, expected) in tests {
+        let mut lexer = Lexer::new(input);
+        let token = lexer.next_token();
+
+        assert_eq!(token.token_type, TokenType::String, "input: {}", input);
+        assert_eq!(unescape(&token.literal).as_bytes(), expected.as_bytes());
+        assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+    }
+}
+
+#[test]
+fn test_invalid_string_escape() {
+    let mut lexer = Lexer::new(r#""bad \q escape"; x"#);
+
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Illegal);
+    assert_eq!(token.literal, "invalid escape sequence in string literal");
+
+    assert_eq!(lexer.next_token().token_type, TokenType::Semicolon);
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident);
+}
+
+#[test]
+fn test_unterminated_string() {
+    let mut lexer = Lexer::new(r#"let s = "hello"#);
+
+    assert_eq!(lexer.next_token().token_type, TokenType::Let);
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident);
+    assert_eq!(lexer.next_token().token_type, TokenType::Assign);
+
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Illegal);
+    assert_eq!(token.literal, "unterminated string literal");
+    assert_eq!((token.line, token.column), (1, 9));
+
+    assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+}
+
+#[test]
+fn test_token_stream() {
+    let input = "let add = fn(x, y) { x + y; };
+add(1, 2);";
+
+    let expected = [
+        (TokenType::Let, "let"),
+        (TokenType::Ident, "add"),
+        (TokenType::Assign, "="),
+        (TokenType::Function, "fn"),
+        (TokenType::LParen, "("),
+        (TokenType::Ident, "x"),
+        (TokenType::Comma, ","),
+        (TokenType::Ident, "y"),
+        (TokenType::RParen, ")"),
+        (TokenType::LBrace, "{"),
+        (TokenType::Ident, "x"),
+        (TokenType::Plus, "+"),
+        (TokenType::Ident, "y"),
+        (TokenType::Semicolon, ";"),
+        (TokenType::RBrace, "}"),
+        (TokenType::Semicolon, ";"),
+        (TokenType::Ident, "add"),
+        (TokenType::LParen, "("),
+        (TokenType::Int, "1"),
+        (TokenType::Comma, ","),
+        (TokenType::Int, "2"),
+        (TokenType::RParen, ")"),
+        (TokenType::Semicolon, ";"),
+        (TokenType::Eof, ""),
+    ];
+
+    let tokens = Lexer::new(input)
+        .tokens()
+        .map(|token| (token.token_type, literal(token)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_null_token() {
+    let mut lexer = Lexer::new("null nullable");
+
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Null);
+    assert_eq!(token.literal, "null");
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident);
+    assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+}
+
+#[test]
+fn test_tokenize() {
+    let input = "let s = \"hi\";\n/* c */ add(s, 2.5)";
+
+    let expected = [
+        (TokenType::Let, "let", 0..3),
+        (TokenType::Ident, "s", 4..5),
+        (TokenType::Assign, "=", 6..7),
+        (TokenType::String, "hi", 8..12),
+        (TokenType::Semicolon, ";", 12..13),
+        (TokenType::Ident, "add", 22..25),
+        (TokenType::LParen, "(", 25..26),
+        (TokenType::Ident, "s", 26..27),
+        (TokenType::Comma, ",", 27..28),
+        (TokenType::Float, "2.5", 29..32),
+        (TokenType::RParen, ")", 32..33),
+    ];
+
+    let tokens = tokenize(input)
+        .expect("tokenize failed")
+        .into_iter()
+        .map(|(token, span)| (token.token_type, literal(token), span))
+        .collect::<Vec<_>>();
+
+    assert_eq!(tokens, expected);
+    assert_eq!(&input[22..25], "add");
+}
+
+#[test]
+fn test_tokenize_stops_at_illegal_token() {
+    assert_eq!(
+        tokenize("let x = 1;\nlet y = \"oops"),
+        Err("unterminated string literal at line 2, col 9".to_string())
+    );
+    assert_eq!(
+        tokenize("1 /* open"),
+        Err("unterminated block comment at line 1, col 3".to_string())
+    );
+}
+
+#[test]
+fn test_precedence_ordering() {
+    let ordered = [
+        Precedence::Lowest,
+        Precedence::Ternary,
+        Precedence::Equals,
+        Precedence::LessGreater,
+        Precedence::BitOr,
+        Precedence::BitXor,
+        Precedence::BitAnd,
+        Precedence::Shift,
+        Precedence::Sum,
+        Precedence::Product,
+        Precedence::Power,
+        Precedence::Prefix,
+        Precedence::Call,
+        Precedence::Index,
+    ];
+
+    for pair in ordered.windows(2) {
+        assert!(
+            pair[0] < pair[1],
+            "{:?} should bind looser than {:?}",
+            pair[0],
+            pair[1]
+        );
+    }
+}
+
+#[test]
+fn test_precedence_table() {
+    let tests = [
+        (TokenType::Question, Precedence::Ternary),
+        (TokenType::Eq, Precedence::Equals),
+        (TokenType::NotEq, Precedence::Equals),
+        (TokenType::Lt, Precedence::LessGreater),
+        (TokenType::Gt, Precedence::LessGreater),
+        (TokenType::LtEq, Precedence::LessGreater),
+        (TokenType::GtEq, Precedence::LessGreater),
+        (TokenType::Pipe, Precedence::BitOr),
+        (TokenType::Caret, Precedence::BitXor),
+        (TokenType::Ampersand, Precedence::BitAnd),
+        (TokenType::ShiftLeft, Precedence::Shift),
+        (TokenType::ShiftRight, Precedence::Shift),
+        (TokenType::Plus, Precedence::Sum),
+        (TokenType::Minus, Precedence::Sum),
+        (TokenType::Asterisk, Precedence::Product),
+        (TokenType::Slash, Precedence::Product),
+        (TokenType::Percent, Precedence::Product),
+        (TokenType::Power, Precedence::Power),
+        (TokenType::LParen, Precedence::Call),
+        (TokenType::Dot, Precedence::Call),
+        (TokenType::LBracket, Precedence::Index),
+        (TokenType::Semicolon, Precedence::Lowest),
+        (TokenType::Ident, Precedence::Lowest),
+    ];
+
+    for (token_type, expected) in tests {
+        assert_eq!(Precedence::of(token_type), expected, "{:?}", token_type);
+    }
+
+    for (i, (token_type, _)) in PRECEDENCES.iter().enumerate() {
+        assert!(
+            PRECEDENCES[i + 1..].iter().all(|(t, _)| t != token_type),
+            "{:?} listed twice in the precedence table",
+            token_type
+        );
+    }
+}
+
+#[test]
+fn test_token_type_display_is_unique() {
+    let names: Vec<String> = TokenType::ALL.iter().map(|t| t.to_string()).collect();
+
+    for (i, name) in names.iter().enumerate() {
+        assert!(
+            !name.is_empty(),
+            "{:?} has an empty Display",
+            TokenType::ALL[i]
+        );
+        assert!(
+            !names[i + 1..].contains(name),
+            "{:?} shares its Display {:?} with another token type",
+            TokenType::ALL[i],
+            name
+        );
+    }
+}
+
+#[test]
+fn test_keywords_lex_to_their_token_type() {
+    for (keyword, expected) in KEYWORDS {
+        let mut lexer = Lexer::new(keyword);
+        let token = lexer.next_token();
+        assert_eq!(token.token_type, *expected, "keyword {:?}", keyword);
+        assert_eq!(token.literal, *keyword);
+    }
+}
+
+#[test]
+fn test_keyword_near_misses_are_identifiers() {
+    let mut near_misses: Vec<String> =
+        ["lets", "iffy", "fnord", "returns", "nullable", "Let", "IF"]
+            .map(String::from)
+            .to_vec();
+    for (keyword, _) in KEYWORDS {
+        near_misses.push(format!("{}_", keyword));
+        near_misses.push(format!("_{}", keyword));
+        near_misses.push(format!("{}1", keyword));
+        near_misses.push(keyword[..keyword.len() - 1].to_string());
+    }
+
+    for input in &near_misses {
+        let mut lexer = Lexer::new(input);
+        let token = lexer.next_token();
+        assert_eq!(token.token_type, TokenType::Ident, "input {:?}", input);
+        assert_eq!(token.literal, input.as_str());
+        assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+    }
+}
+
+#[test]
+fn test_dump_tokens() {
+    let dump = dump_tokens("let x = 5;\nx").unwrap();
+
+    assert_eq!(
+        dump,
+        "Let \"let\" 0..3
+Ident \"x\" 4..5
+Assign \"=\" 6..7
+Int \"5\" 8..9
+Semicolon \";\" 9..10
+Ident \"x\" 11..12
+"
+    );
+    assert!(dump_tokens("/* open").is_err());
+}
+
+#[test]
+fn test_power_token() {
+    let tests = [
+        (
+            "2 ** 3",
+            vec![
+                (TokenType::Int, "2"),
+                (TokenType::Power, "**"),
+                (TokenType::Int, "3"),
+            ],
+        ),
+        (
+            "2 * 3",
+            vec![
+                (TokenType::Int, "2"),
+                (TokenType::Asterisk, "*"),
+                (TokenType::Int, "3"),
+            ],
+        ),
+        (
+            "2 *** 3",
+            vec![
+                (TokenType::Int, "2"),
+                (TokenType::Power, "**"),
+                (TokenType::Asterisk, "*"),
+                (TokenType::Int, "3"),
+            ],
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let actual: Vec<_> = tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| (token.token_type, literal(token)))
+            .collect();
+        assert_eq!(actual, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_precedence_lower() {
+    assert_eq!(Precedence::Power.lower(), Precedence::Product);
+    assert_eq!(Precedence::Lowest.lower(), Precedence::Lowest);
+    assert_eq!(Precedence::Sum.lower(), Precedence::Shift);
+    assert_eq!(Precedence::BitOr.lower(), Precedence::LessGreater);
+    assert_eq!(Precedence::Equals.lower(), Precedence::Ternary);
+    assert!(Precedence::is_right_associative(TokenType::Power));
+    assert!(Precedence::is_right_associative(TokenType::Question));
+    assert!(!Precedence::is_right_associative(TokenType::Minus));
+}
+
+#[test]
+fn test_input_edges_end_with_eof() {
+    let tests = [
+        ("", vec![]),
+        ("5", vec![(TokenType::Int, "5")]),
+        ("x", vec![(TokenType::Ident, "x")]),
+        ("=", vec![(TokenType::Assign, "=")]),
+        ("1.5", vec![(TokenType::Float, "1.5")]),
+        (
+            "let ab = 12",
+            vec![
+                (TokenType::Let, "let"),
+                (TokenType::Ident, "ab"),
+                (TokenType::Assign, "="),
+                (TokenType::Int, "12"),
+            ],
+        ),
+        (
+            "a==b",
+            vec![
+                (TokenType::Ident, "a"),
+                (TokenType::Eq, "=="),
+                (TokenType::Ident, "b"),
+            ],
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let mut lexer = Lexer::new(input);
+        for (expected_type, expected_literal) in expected {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, expected_type, "input: {:?}", input);
+            assert_eq!(token.literal, expected_literal, "input: {:?}", input);
+        }
+
+        for _ in 0..2 {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, TokenType::Eof, "input: {:?}", input);
+            assert_eq!(token.literal, "");
+        }
+    }
+}
+
+#[test]
+fn test_unicode_identifiers() {
+    let input = "let café = 5;\nλ(café, naïve_2) + 日本;";
+    let expected = [
+        (TokenType::Let, "let", 1, 1),
+        (TokenType::Ident, "café", 1, 5),
+        (TokenType::Assign, "=", 1, 10),
+        (TokenType::Int, "5", 1, 12),
+        (TokenType::Semicolon, ";", 1, 13),
+        (TokenType::Ident, "λ", 2, 1),
+        (TokenType::LParen, "(", 2, 2),
+        (TokenType::Ident, "café", 2, 3),
+        (TokenType::Comma, ",", 2, 7),
+        (TokenType::Ident, "naïve_2", 2, 9),
+        (TokenType::RParen, ")", 2, 16),
+        (TokenType::Plus, "+", 2, 18),
+        (TokenType::Ident, "日本", 2, 20),
+        (TokenType::Semicolon, ";", 2, 22),
+        (TokenType::Eof, "", 2, 23),
+    ];
+
+    let mut lexer = Lexer::new(input);
+    for (token_type, literal, line, column) in expected {
+        let token = lexer.next_token();
+        assert_eq!(
+            (token.token_type, &*token.literal, token.line, token.column),
+            (token_type, literal, line, column)
+        );
+        if token_type != TokenType::Eof {
+            assert_eq!(&input[lexer.span()], literal);
+        }
+    }
+}
+
+#[test]
+fn test_non_ascii_digits_are_not_numbers() {
+    let mut lexer = Lexer::new("1٣");
+
+    let token = lexer.next_token();
+    assert_eq!((token.token_type, &*token.literal), (TokenType::Int, "1"));
+    let token = lexer.next_token();
+    assert_eq!(token.token_type, TokenType::Illegal);
+    assert_eq!((token.line, token.column), (1, 2));
+    assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+}
+
+#[test]
+fn test_comparison_tokens() {
+    let actual: Vec<_> = tokenize("a <= b >= c < d > e <=f")
+        .unwrap()
+        .into_iter()
+        .map(|(token, _)| (token.token_type, literal(token)))
+        .collect();
+
+    assert_eq!(
+        actual,
+        vec![
+            (TokenType::Ident, "a"),
+            (TokenType::LtEq, "<="),
+            (TokenType::Ident, "b"),
+            (TokenType::GtEq, ">="),
+            (TokenType::Ident, "c"),
+            (TokenType::Lt, "<"),
+            (TokenType::Ident, "d"),
+            (TokenType::Gt, ">"),
+            (TokenType::Ident, "e"),
+            (TokenType::LtEq, "<="),
+            (TokenType::Ident, "f"),
+        ]
+    );
+}
+
+#[test]
+fn test_preserve_comments() {
+    let input = "// hi\n5";
+
+    let mut lexer = Lexer::new(input).preserve_comments(true);
+    let comment = lexer.next_token();
+    assert_eq!(
+        (
+            comment.token_type,
+            &*comment.literal,
+            comment.line,
+            comment.column
+        ),
+        (TokenType::Comment, "// hi", 1, 1)
+    );
+    assert_eq!(&input[lexer.span()], "// hi");
+    let int = lexer.next_token();
+    assert_eq!((int.token_type, &*int.literal), (TokenType::Int, "5"));
+    assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+
+    let mut lexer = Lexer::new(input);
+    let int = lexer.next_token();
+    assert_eq!((int.token_type, &*int.literal), (TokenType::Int, "5"));
+    assert_eq!(lexer.next_token().token_type, TokenType::Eof);
+}
+
+#[test]
+fn test_preserve_block_comments() {
+    let mut lexer = Lexer::new("x /* a /* nested */ b */ // end").preserve_comments(true);
+    let expected = [
+        (TokenType::Ident, "x"),
+        (TokenType::Comment, "/* a /* nested */ b */"),
+        (TokenType::Comment, "// end"),
+        (TokenType::Eof, ""),
+    ];
+
+    for (token_type, literal) in expected {
+        let token = lexer.next_token();
+        assert_eq!((token.token_type, &*token.literal), (token_type, literal));
+    }
+
+    let mut lexer = Lexer::new("/* open").preserve_comments(true);
+    assert_eq!(lexer.next_token().token_type, TokenType::Illegal);
+}
+
+#[test]
+fn test_bitwise_tokens() {
+    let actual: Vec<_> = tokenize("a & b | c ^ d << e >> f <<= g >>h")
+        .unwrap()
+        .into_iter()
+        .map(|(token, _)| (token.token_type, literal(token)))
+        .collect();
+
+    assert_eq!(
+        actual,
+        vec![
+            (TokenType::Ident, "a"),
+            (TokenType::Ampersand, "&"),
+            (TokenType::Ident, "b"),
+            (TokenType::Pipe, "|"),
+            (TokenType::Ident, "c"),
+            (TokenType::Caret, "^"),
+            (TokenType::Ident, "d"),
+            (TokenType::ShiftLeft, "<<"),
+            (TokenType::Ident, "e"),
+            (TokenType::ShiftRight, ">>"),
+            (TokenType::Ident, "f"),
+            (TokenType::ShiftLeft, "<<"),
+            (TokenType::Assign, "="),
+            (TokenType::Ident, "g"),
+            (TokenType::ShiftRight, ">>"),
+            (TokenType::Ident, "h"),
+        ]
+    );
+}
+
+#[test]
+fn test_question_token() {
+    let actual: Vec<_> = tokenize("a ? b : c")
+        .unwrap()
+        .into_iter()
+        .map(|(token, _)| (token.token_type, literal(token)))
+        .collect();
+
+    assert_eq!(
+        actual,
+        vec![
+            (TokenType::Ident, "a"),
+            (TokenType::Question, "?"),
+            (TokenType::Ident, "b"),
+            (TokenType::Colon, ":"),
+            (TokenType::Ident, "c"),
+        ]
+    );
+}
@@ -1,28 +1,66 @@
 use core::fmt;
+use std::borrow::Cow;
 
-#[derive(Debug, PartialEq, Eq, Clone, Default, Copy)]
-pub enum TokenType {
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+// Declares `TokenType` together with `TokenType::ALL`, so the list of every
+// variant can't fall out of step with the enum.
+macro_rules! token_types {
+    ($($(#[$attr:meta])* $variant:ident,)*) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Default, Copy)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub enum TokenType {
+            $($(#[$attr])* $variant,)*
+        }
+
+        impl TokenType {
+            /// Every token type, in declaration order.
+            pub const ALL: [TokenType; [$(TokenType::$variant),*].len()] =
+                [$(TokenType::$variant),*];
+        }
+    };
+}
+
+token_types! {
     Illegal,
     Eof,
     // Identifiers + literals
-    Ident, // add, foobar, x, y, ...
-    Int,   // 1343456
+    Ident,   // add, foobar, x, y, ...
+    Int,     // 1343456
+    Float,   // 3.14
+    String,  // "foobar"
+    Comment, // only produced when the lexer preserves comments
     // Operators
     Assign,
     Plus,
     Minus,
     Bang,
     Asterisk,
+    Power,
     Slash,
+    Percent,
     Lt,
     Gt,
+    LtEq,
+    GtEq,
+    Ampersand,
+    Pipe,
+    Caret,
+    ShiftLeft,
+    ShiftRight,
     // Delimiters
     Comma,
+    Dot,
     Semicolon,
+    Colon,
+    Question,
     LParen,
     RParen,
     LBrace,
     RBrace,
+    LBracket,
+    RBracket,
     // Keywords
     Function,
     Let,
@@ -31,24 +69,56 @@ pub enum TokenType {
     Else,
     If,
     Return,
+    Null,
+    Macro,
     Eq,
     NotEq,
     #[default]
     NotSet,
 }
 
+/// Reserved words and the token types the lexer produces for them.
+pub const KEYWORDS: &[(&str, TokenType)] = &[
+    ("fn", TokenType::Function),
+    ("let", TokenType::Let),
+    ("true", TokenType::True),
+    ("false", TokenType::False),
+    ("else", TokenType::Else),
+    ("if", TokenType::If),
+    ("return", TokenType::Return),
+    ("null", TokenType::Null),
+    ("macro", TokenType::Macro),
+];
 
-#[derive(Debug, Eq, PartialEq, Clone, Default, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Token<'a> {
     pub token_type: TokenType,
-    pub literal: &'a str,
+    // Borrowed from the source for lexed tokens; owned for tokens built at
+    // runtime, such as literals spliced in by `unquote`
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub literal: Cow<'a, str>,
+    // 1-based position of the first character of the token
+    pub line: usize,
+    pub column: usize,
 }
 
 impl<'a> Token<'a> {
-    pub fn new(token_type: TokenType, literal: &'a str) -> Self {
+    pub fn new(token_type: TokenType, literal: impl Into<Cow<'a, str>>) -> Self {
         Self {
             token_type,
-            literal,
+            literal: literal.into(),
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub fn at(self, line: usize, column: usize) -> Self {
+        Self {
+            line,
+            column,
+            ..self
         }
     }
 }
@@ -70,20 +140,37 @@ impl fmt::Display for TokenType {
             TokenType::Eof => "Eof",
             TokenType::Ident => "Ident",
             TokenType::Int => "Int",
+            TokenType::Float => "Float",
+            TokenType::String => "String",
+            TokenType::Comment => "Comment",
             TokenType::Assign => "=",
             TokenType::Plus => "+",
             TokenType::Minus => "-",
             TokenType::Bang => "!",
             TokenType::Asterisk => "*",
+            TokenType::Power => "**",
             TokenType::Slash => "/",
+            TokenType::Percent => "%",
             TokenType::Lt => "<",
             TokenType::Gt => ">",
+            TokenType::LtEq => "<=",
+            TokenType::GtEq => ">=",
+            TokenType::Ampersand => "&",
+            TokenType::Pipe => "|",
+            TokenType::Caret => "^",
+            TokenType::ShiftLeft => "<<",
+            TokenType::ShiftRight => ">>",
             TokenType::Comma => ",",
+            TokenType::Dot => ".",
             TokenType::Semicolon => ";",
+            TokenType::Colon => ":",
+            TokenType::Question => "?",
             TokenType::LParen => "(",
             TokenType::RParen => ")",
             TokenType::LBrace => "{",
             TokenType::RBrace => "}",
+            TokenType::LBracket => "[",
+            TokenType::RBracket => "]",
             TokenType::Function => "function",
             TokenType::Let => "let",
             TokenType::True => "true",
@@ -91,6 +178,8 @@ impl fmt::Display for TokenType {
             TokenType::Else => "else",
             TokenType::If => "if",
             TokenType::Return => "return",
+            TokenType::Null => "null",
+            TokenType::Macro => "macro",
             TokenType::Eq => "==",
             TokenType::NotEq => "!=",
             TokenType::NotSet => "notSet",
@@ -98,3 +187,27 @@ impl fmt::Display for TokenType {
         write!(f, "{}", token_str)
     }
 }
+
+// Identifiers and operators are borrowed from the JSON input. Strings with JSON
+// escapes can't be borrowed, so they are rejected rather than copied.
+#[cfg(feature = "serde")]
+pub(crate) fn borrow_str<'de: 'a, 'a, D>(deserializer: D) -> Result<&'a str, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StrVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for StrVisitor {
+        type Value = &'de str;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a string borrowed from the input, without escapes")
+        }
+
+        fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+            Ok(value)
+        }
+    }
+
+    deserializer.deserialize_str(StrVisitor)
+}
@@ -1,7 +1,146 @@
+#![allow(clippy::module_inception)]
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
 pub mod ast;
+pub mod compiler;
+pub mod diagnostic;
 pub mod eval;
-pub use eval::eval_program;
+use eval::object::Object;
+pub use eval::{
+    Context, DEFAULT_MAX_DEPTH, Environment, eval_program, eval_program_with_context,
+    eval_program_with_output, with_prelude,
+};
 pub mod lexer;
 pub use lexer::lexer::Lexer;
 pub mod parser;
-pub use parser::parser::Parser;
+pub use parser::{ParseError, parser::Parser};
+pub mod repl;
+pub mod vm;
+
+// Every nested expression takes host stack frames in the recursive passes
+// over the tree, so the stack is grown on demand instead of bounding how deep
+// a program may nest.
+const STACK_RED_ZONE: usize = 128 * 1024;
+const STACK_GROWTH: usize = 2 * 1024 * 1024;
+
+pub(crate) fn grow_stack<R>(f: impl FnOnce() -> R) -> R {
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, f)
+}
+
+/// Why [`run`] failed: the source did not parse, or evaluating it produced an
+/// error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunError {
+    Parse(Vec<ParseError>),
+    Runtime(String),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(errors) => write!(
+                f,
+                "{}",
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            RunError::Runtime(message) => write!(f, "ERROR: {}", message),
+        }
+    }
+}
+
+/// Lexes, parses and evaluates `source` in a fresh environment holding the
+/// prelude.
+pub fn run(source: &str) -> Result<Object<'_>, RunError> {
+    run_with_max_depth(source, DEFAULT_MAX_DEPTH)
+}
+
+/// Like [`run`], but evaluation fails with "maximum recursion depth exceeded"
+/// once function calls nest deeper than `max_depth`.
+pub fn run_with_max_depth(source: &str, max_depth: usize) -> Result<Object<'_>, RunError> {
+    run_timed_with_max_depth(source, max_depth).0
+}
+
+/// Runs `source` without touching stdin or stdout, for embedding (e.g. a
+/// browser playground).
+///
+/// Returns anything written by `puts`, followed by the program's final value
+/// or its errors, one message per line.
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn eval_to_string(source: &str) -> String {
+    let mut output = Vec::new();
+
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+
+    let result = match program {
+        Some(mut program) if parser.errors.is_empty() => {
+            let mut env = Environment::new();
+            with_prelude(&mut env);
+            let result = eval_program_with_output(&mut program, &mut env, &mut output)
+                .map(|object| object.to_string())
+                .map_err(RunError::Runtime);
+            env.clear();
+            result
+        }
+        _ => Err(RunError::Parse(parser.errors)),
+    };
+
+    let mut out = String::from_utf8_lossy(&output).into_owned();
+    match result {
+        Ok(value) => out.push_str(&value),
+        Err(err) => out.push_str(&err.to_string()),
+    }
+    out
+}
+
+/// Wall-clock time spent in each phase of [`run_timed`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub eval: Duration,
+}
+
+/// Like [`run`], but also reports how long parsing and evaluation took.
+/// Evaluation is not timed if the source failed to parse.
+pub fn run_timed(source: &str) -> (Result<Object<'_>, RunError>, Timings) {
+    run_timed_with_max_depth(source, DEFAULT_MAX_DEPTH)
+}
+
+fn run_timed_with_max_depth(
+    source: &str,
+    max_depth: usize,
+) -> (Result<Object<'_>, RunError>, Timings) {
+    let mut timings = Timings::default();
+
+    let started = Instant::now();
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    timings.parse = started.elapsed();
+
+    let result = match program {
+        Some(mut program) if parser.errors.is_empty() => {
+            let mut stdout = std::io::stdout();
+            let mut ctx = Context::new(&mut stdout).with_max_depth(max_depth);
+            let mut env = Environment::new();
+            with_prelude(&mut env);
+
+            let started = Instant::now();
+            let result = eval_program_with_context(&mut program, &mut env, &mut ctx);
+            timings.eval = started.elapsed();
+            result.map_err(RunError::Runtime)
+        }
+        _ => Err(RunError::Parse(parser.errors)),
+    };
+
+    (result, timings)
+}
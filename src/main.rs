@@ -1,36 +1,98 @@
-use std::io::Write;
+use interpreter_book::{
+    RunError,
+    lexer::dump_tokens,
+    parser::{dump_ast, format_errors},
+    repl, run_timed,
+};
 
-use interpreter_book::{Lexer, Parser, eval_program};
+const USAGE: &str = "usage: interpreter-book repl
+       interpreter-book run [--tokens | --ast | --time] <file>
+       interpreter-book tokens <file>
+       interpreter-book ast <file>
+       interpreter-book <file>";
 
 fn main() {
-    let mut args = std::env::args();
-    args.next();
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["repl"] => start_repl(),
+        ["tokens", path] | ["run", "--tokens", path] => print_tokens(path),
+        ["ast", path] | ["run", "--ast", path] => print_ast(path),
+        ["run", "--time", path] => run_file(path, true),
+        ["run", path] | [path] => run_file(path, false),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2)
+        }
+    }
+}
+
+fn start_repl() {
     println!("Hello! This is the Monkey programming language written in Rust!");
     println!("Feel free to type in commands.");
-    if args.next().unwrap() == "repl" {
-        loop {
-            let mut input = String::new();
-            print!("> ");
-            let _ = std::io::stdout().flush();
-            std::io::stdin().read_line(&mut input).unwrap();
-
-            if input.trim_end().is_empty() {
-                println!("Exiting Repl");
-                std::process::exit(0)
-            }
-
-            let lexer = Lexer::new(&input);
-            let mut parser = Parser::new(lexer);
-            if let Some(mut program) = parser.parse_program() {
-                if parser.errors.is_empty() {
-                    println!("{}", eval_program(&mut program));
-                } else {
-                    for error in parser.errors.iter() {
-                        println!("{}", error)
-                    }
-                }
-            }
+
+    let stdin = std::io::stdin();
+    if let Err(err) = repl::start(stdin.lock(), &mut std::io::stdout()) {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    }
+}
+
+fn read_source(path: &str) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("could not read {}: {}", path, err);
+            std::process::exit(1)
+        }
+    }
+}
+
+fn print_tokens(path: &str) {
+    match dump_tokens(&read_source(path)) {
+        Ok(dump) => print!("{}", dump),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1)
+        }
+    }
+}
+
+fn print_ast(path: &str) {
+    let source = read_source(path);
+
+    match dump_ast(&source) {
+        Ok(dump) => print!("{}", dump),
+        Err(errors) => {
+            eprint!("{}", format_errors(&errors, &source));
+            std::process::exit(1)
+        }
+    }
+}
+
+fn run_file(path: &str, time: bool) {
+    let source = read_source(path);
+
+    let (result, timings) = run_timed(&source);
+    if time {
+        eprintln!("parse: {:?}", timings.parse);
+        eprintln!("eval: {:?}", timings.eval);
+    }
+
+    match result {
+        Ok(object) => println!("{}", object),
+        Err(RunError::Runtime(message)) => {
+            eprintln!("ERROR: {}", message);
+            std::process::exit(1)
+        }
+        Err(RunError::Parse(errors)) => {
+            eprint!("{}", format_errors(&errors, &source));
+            std::process::exit(1)
         }
     }
 }
@@ -0,0 +1,49 @@
+use core::fmt;
+
+use crate::lexer::token::{Token, TokenType};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    // 1-based position of the offending token
+    pub line: usize,
+    pub column: usize,
+    // The token type the parser was looking for, if it wanted a specific one
+    pub expected: Option<TokenType>,
+    pub got: TokenType,
+}
+
+impl ParseError {
+    pub fn new(message: String, token: &Token<'_>, expected: Option<TokenType>) -> Self {
+        Self {
+            message,
+            line: token.line,
+            column: token.column,
+            expected,
+            got: token.token_type,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, col {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+const MONKEY_FACE: &str = "Woops! We ran into some monkey business here!";
+
+/// Formats `errors` as one block for the user: a header followed by each error
+/// rendered against the offending line of `source`.
+pub fn format_errors(errors: &[ParseError], source: &str) -> String {
+    let mut out = format!("{}\n parser errors:\n", MONKEY_FACE);
+    for error in errors {
+        out.push_str(&error.render(source));
+        out.push('\n');
+    }
+    out
+}
@@ -1,4 +1,7 @@
 #[cfg(test)]
 mod tests;
 
+pub mod error;
+pub use error::{ParseError, format_errors};
 pub mod parser;
+pub use parser::dump_ast;
@@ -1,18 +1,34 @@
+use std::borrow::Cow;
+
+use super::error::ParseError;
 use crate::{
     ast::ast::{Expression, Identifier, Program, Statement},
     lexer::{
-        lexer::Lexer,
+        lexer::{Lexer, unescape},
         precedence::Precedence,
         token::{Token, TokenType},
     },
 };
 
+const I64_MIN_LITERAL: &str = "-9223372036854775808";
+
+/// How deeply expressions may nest (parentheses, prefix operators, operands
+/// of right-associative operators, blocks) before parsing gives up. Deeper
+/// input is reported as an error rather than risking the host stack, which
+/// can't be grown when running as wasm.
+pub const MAX_NESTING_DEPTH: usize = 1_000;
+
 #[derive(Debug, Clone)]
 pub struct Parser<'a> {
     pub lexer: Lexer<'a>,
     pub curr_token: Token<'a>,
     pub peek_token: Token<'a>,
-    pub errors: Vec<String>,
+    pub errors: Vec<ParseError>,
+    // Byte ranges of `curr_token` and `peek_token` in the input
+    curr_span: (usize, usize),
+    peek_span: (usize, usize),
+    // How many expressions are currently being parsed inside one another
+    depth: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -22,6 +38,9 @@ impl<'a> Parser<'a> {
             curr_token: Token::default(),
             peek_token: Token::default(),
             errors: vec![],
+            curr_span: (0, 0),
+            peek_span: (0, 0),
+            depth: 0,
         };
 
         // Read 2 tokens so curr_token and next_token are both set
@@ -32,32 +51,133 @@ impl<'a> Parser<'a> {
     }
 
     pub fn next_token(&mut self) {
-        self.curr_token = self.peek_token;
+        self.curr_token = std::mem::take(&mut self.peek_token);
+        self.curr_span = self.peek_span;
         self.peek_token = self.lexer.next_token();
+        while self.peek_token.token_type == TokenType::Comment {
+            self.peek_token = self.lexer.next_token();
+        }
+        let span = self.lexer.span();
+        self.peek_span = (span.start, span.end);
+    }
+
+    // Span from `start` to the end of the current token, which is the last
+    // token of the node that was just parsed
+    fn span_from(&self, start: usize) -> Option<(usize, usize)> {
+        Some((start, self.curr_span.1))
+    }
+
+    // Lexed tokens borrow their literal from the input, so the text can be
+    // kept in the AST for as long as the input lives
+    fn curr_literal(&self) -> &'a str {
+        match self.curr_token.literal {
+            Cow::Borrowed(literal) => literal,
+            Cow::Owned(_) => unreachable!("the lexer only produces borrowed literals"),
+        }
+    }
+
+    fn curr_identifier(&self) -> Identifier<'a> {
+        Identifier {
+            token: self.curr_token.clone(),
+            value: self.curr_literal(),
+            span: Some(self.curr_span),
+        }
     }
 
     pub fn parse_program(&mut self) -> Option<Program<'a>> {
         let mut program = Program::new();
 
         while !self.curr_token_is(TokenType::Eof) {
-            if let Some(stmt) = self.parse_statement() {
-                program.statements.push(stmt);
+            match self.parse_statement() {
+                Some(stmt) => program.statements.push(stmt),
+                None => self.synchronize(),
             }
             self.next_token();
         }
         Some(program)
     }
 
+    // Skips the rest of a statement that failed to parse so that the following
+    // statements can still be parsed and report their own errors. Stops before
+    // a closing brace so an enclosing block still sees its end.
+    fn synchronize(&mut self) {
+        while !self.curr_token_is(TokenType::Semicolon)
+            && !self.curr_token_is(TokenType::Eof)
+            && !self.peek_token_is(TokenType::RBrace)
+            && !self.peek_token_is(TokenType::Eof)
+        {
+            self.next_token();
+        }
+    }
+
     pub fn parse_statement(&mut self) -> Option<Statement<'a>> {
         match self.curr_token.token_type {
             TokenType::Let => self.parse_let_statement(),
             TokenType::Return => self.parse_return_statement(),
+            // Empty statement, e.g. the second `;` in `5;;`
+            TokenType::Semicolon => None,
+            TokenType::Ident if self.peek_token_is(TokenType::Assign) => {
+                self.parse_assign_statement()
+            }
+            TokenType::Function if self.peek_token_is(TokenType::Ident) => {
+                self.parse_function_declaration()
+            }
+            TokenType::LBrace if self.brace_starts_block() => Some(self.parse_block_statement()),
             _ => self.parse_expression_statement(),
         }
     }
 
+    // At statement position `{` opens either a block or a hash literal. It is
+    // a hash if it is empty or a `:` appears before any `;` or the closing
+    // brace at the top nesting level; otherwise it is a block. A `:` that
+    // closes a ternary's `?` doesn't count.
+    fn brace_starts_block(&self) -> bool {
+        let mut lexer = self.lexer.clone();
+        let mut token = self.peek_token.clone();
+        let mut depth = 0;
+        let mut open_ternaries = 0;
+
+        if token.token_type == TokenType::RBrace {
+            return false;
+        }
+
+        loop {
+            match token.token_type {
+                TokenType::LBrace | TokenType::LParen | TokenType::LBracket => depth += 1,
+                TokenType::RBrace if depth == 0 => return true,
+                TokenType::RBrace | TokenType::RParen | TokenType::RBracket => depth -= 1,
+                TokenType::Question if depth == 0 => open_ternaries += 1,
+                TokenType::Colon if depth == 0 && open_ternaries > 0 => open_ternaries -= 1,
+                TokenType::Colon if depth == 0 => return false,
+                TokenType::Semicolon if depth == 0 => return true,
+                TokenType::Eof => return true,
+                _ => {}
+            }
+            token = lexer.next_token();
+        }
+    }
+
     pub fn parse_return_statement(&mut self) -> Option<Statement<'a>> {
-        let token = self.curr_token;
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
+
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+            return Some(Statement::Return {
+                token,
+                value: Expression::None,
+                span: self.span_from(start),
+            });
+        }
+
+        if self.peek_token_is(TokenType::RBrace) || self.peek_token_is(TokenType::Eof) {
+            return Some(Statement::Return {
+                token,
+                value: Expression::None,
+                span: self.span_from(start),
+            });
+        }
+
         self.next_token();
 
         let value = self.parse_expression_w_precedence(Precedence::Lowest)?;
@@ -66,19 +186,21 @@ impl<'a> Parser<'a> {
             self.next_token();
         }
 
-        Some(Statement::Return { token, value })
+        Some(Statement::Return {
+            token,
+            value,
+            span: self.span_from(start),
+        })
     }
 
     pub fn parse_let_statement(&mut self) -> Option<Statement<'a>> {
-        let token = self.curr_token;
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
         if !self.expect_peek(TokenType::Ident) {
             return None;
         }
 
-        let name = Identifier {
-            token: self.curr_token,
-            value: self.curr_token.literal,
-        };
+        let name = self.curr_identifier();
 
         if !self.expect_peek(TokenType::Assign) {
             return None;
@@ -92,11 +214,79 @@ impl<'a> Parser<'a> {
             self.next_token();
         }
 
-        Some(Statement::Let { token, name, value })
+        Some(Statement::Let {
+            token,
+            name,
+            value,
+            span: self.span_from(start),
+        })
+    }
+
+    pub fn parse_assign_statement(&mut self) -> Option<Statement<'a>> {
+        let start = self.curr_span.0;
+        let name = self.curr_identifier();
+
+        self.next_token();
+        let token = self.curr_token.clone();
+        self.next_token();
+
+        let value = self.parse_expression_w_precedence(Precedence::Lowest)?;
+
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+        }
+
+        Some(Statement::Assign {
+            token,
+            name,
+            value,
+            span: self.span_from(start),
+        })
+    }
+
+    // `fn name(...) { ... }` is sugar for `let name = fn(...) { ... };`
+    pub fn parse_function_declaration(&mut self) -> Option<Statement<'a>> {
+        let token = self.curr_token.clone();
+        let let_token =
+            Token::new(TokenType::Let, "let").at(self.curr_token.line, self.curr_token.column);
+        let start = self.curr_span.0;
+
+        self.next_token();
+        let name = self.curr_identifier();
+
+        if !self.expect_peek(TokenType::LParen) {
+            return None;
+        }
+
+        let parameters = self.parse_function_parameters()?;
+
+        if !self.expect_peek(TokenType::LBrace) {
+            return None;
+        }
+
+        let body = Box::new(self.parse_block_statement());
+        let function_span = self.span_from(start);
+
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+        }
+
+        Some(Statement::Let {
+            token: let_token,
+            name,
+            value: Expression::Function {
+                token,
+                parameters,
+                body,
+                span: function_span,
+            },
+            span: self.span_from(start),
+        })
     }
 
     pub fn parse_function_literal(&mut self) -> Option<Expression<'a>> {
-        let token = self.curr_token;
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
         if !self.expect_peek(TokenType::LParen) {
             return None;
         }
@@ -113,6 +303,30 @@ impl<'a> Parser<'a> {
             token,
             parameters,
             body,
+            span: self.span_from(start),
+        })
+    }
+
+    pub fn parse_macro_literal(&mut self) -> Option<Expression<'a>> {
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
+        if !self.expect_peek(TokenType::LParen) {
+            return None;
+        }
+
+        let parameters = self.parse_function_parameters()?;
+
+        if !self.expect_peek(TokenType::LBrace) {
+            return None;
+        }
+
+        let body = Box::new(self.parse_block_statement());
+
+        Some(Expression::MacroLiteral {
+            token,
+            parameters,
+            body,
+            span: self.span_from(start),
         })
     }
 
@@ -134,7 +348,7 @@ impl<'a> Parser<'a> {
         }
     }
 
-    pub fn errors(&self) -> Vec<String> {
+    pub fn errors(&self) -> Vec<ParseError> {
         self.errors.clone()
     }
 
@@ -143,22 +357,51 @@ impl<'a> Parser<'a> {
             "expected next token to be {:?}, got {:?} instead",
             token, self.peek_token.token_type
         );
-        self.errors.push(message);
+        self.errors
+            .push(ParseError::new(message, &self.peek_token, Some(token)));
     }
 
     pub fn parse_expression_prefix(&mut self) -> Option<Expression<'a>> {
         match self.curr_token.token_type {
             TokenType::Ident => Some(self.parse_identifier()),
             TokenType::Int => self.parse_integer_literal(),
+            TokenType::Float => self.parse_float_literal(),
+            TokenType::String => Some(self.parse_string_literal()),
             TokenType::Plus | TokenType::Minus | TokenType::Bang => self.parse_prefix_expression(),
             TokenType::True | TokenType::False => self.parse_boolean_expression(),
+            TokenType::Null => Some(self.parse_null_literal()),
             TokenType::LParen => self.parse_grouped_expression(),
             TokenType::If => self.parse_if_expression(),
             TokenType::Function => self.parse_function_literal(),
-            _ => None,
+            TokenType::Macro => self.parse_macro_literal(),
+            TokenType::LBracket => self.parse_array_literal(),
+            TokenType::LBrace => self.parse_hash_literal(),
+            TokenType::Illegal => {
+                self.illegal_token_error();
+                None
+            }
+            _ => {
+                self.no_prefix_parse_error();
+                None
+            }
         }
     }
 
+    fn no_prefix_parse_error(&mut self) {
+        let message = format!(
+            "no prefix parse function for {:?} found",
+            self.curr_token.token_type
+        );
+        self.errors
+            .push(ParseError::new(message, &self.curr_token, None));
+    }
+
+    pub fn illegal_token_error(&mut self) {
+        let message = self.curr_token.literal.to_string();
+        self.errors
+            .push(ParseError::new(message, &self.curr_token, None));
+    }
+
     pub fn parse_expression_infix(&mut self, expression: Expression<'a>) -> Option<Expression<'a>> {
         match self.curr_token.token_type {
             TokenType::Eq
@@ -166,17 +409,48 @@ impl<'a> Parser<'a> {
             | TokenType::Minus
             | TokenType::Plus
             | TokenType::Asterisk
+            | TokenType::Power
             | TokenType::Slash
+            | TokenType::Percent
             | TokenType::Gt
-            | TokenType::Lt => self.parse_infix_expression(expression),
+            | TokenType::Lt
+            | TokenType::GtEq
+            | TokenType::LtEq
+            | TokenType::Ampersand
+            | TokenType::Pipe
+            | TokenType::Caret
+            | TokenType::ShiftLeft
+            | TokenType::ShiftRight => self.parse_infix_expression(expression),
             TokenType::LParen => self.parse_call_expression(expression),
+            TokenType::Dot => self.parse_method_call(expression),
+            TokenType::Question => self.parse_ternary_expression(expression),
+            TokenType::LBracket => self.parse_index_expression(expression),
             _ => None,
         }
     }
 
     pub fn parse_prefix_expression(&mut self) -> Option<Expression<'a>> {
-        let token = self.curr_token;
-        let operator = self.curr_token.literal;
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
+        let operator = self.curr_literal();
+
+        // The magnitude of i64::MIN doesn't fit in an i64, so the minus is
+        // folded into the literal instead of negating it at runtime.
+        if token.token_type == TokenType::Minus
+            && self.peek_token_is(TokenType::Int)
+            && self.peek_token.literal == I64_MIN_LITERAL[1..]
+        {
+            self.next_token();
+            return Some(Expression::Integer {
+                token: Token {
+                    token_type: TokenType::Int,
+                    literal: I64_MIN_LITERAL.into(),
+                    ..token
+                },
+                value: i64::MIN,
+                span: self.span_from(start),
+            });
+        }
 
         self.next_token();
 
@@ -186,14 +460,22 @@ impl<'a> Parser<'a> {
             token,
             operator,
             right: Box::new(right),
+            span: self.span_from(start),
         })
     }
 
     pub fn parse_infix_expression(&mut self, left: Expression<'a>) -> Option<Expression<'a>> {
-        let token = self.curr_token;
-        let operator = self.curr_token.literal;
+        let token = self.curr_token.clone();
+        let operator = self.curr_literal();
+        let start = left.span().map_or(self.curr_span.0, |(start, _)| start);
         let left = Box::new(left);
-        let precedence = self.curr_precedence();
+        // A right-associative operator parses its right operand one tier
+        // lower, so a following operator of the same tier nests to the right.
+        let precedence = if Precedence::is_right_associative(token.token_type) {
+            self.curr_precedence().lower()
+        } else {
+            self.curr_precedence()
+        };
         self.next_token();
         let right = Box::new(self.parse_expression_w_precedence(precedence)?);
 
@@ -202,31 +484,87 @@ impl<'a> Parser<'a> {
             operator,
             right,
             left,
+            span: self.span_from(start),
         })
     }
 
-    pub fn parse_identifier(&self) -> Expression<'a> {
-        Expression::Identifier(Identifier {
-            token: self.curr_token,
-            value: self.curr_token.literal,
+    // `condition ? then : otherwise`. The branch after `:` is parsed one tier
+    // lower, like any right-associative operator, so a chain nests to the right.
+    fn parse_ternary_expression(&mut self, condition: Expression<'a>) -> Option<Expression<'a>> {
+        let token = self.curr_token.clone();
+        let start = condition
+            .span()
+            .map_or(self.curr_span.0, |(start, _)| start);
+        let precedence = self.curr_precedence().lower();
+
+        self.next_token();
+        let then = Box::new(self.parse_expression_w_precedence(Precedence::Lowest)?);
+
+        if !self.expect_peek(TokenType::Colon) {
+            return None;
+        }
+
+        self.next_token();
+        let otherwise = Box::new(self.parse_expression_w_precedence(precedence)?);
+
+        Some(Expression::Ternary {
+            token,
+            condition: Box::new(condition),
+            then,
+            otherwise,
+            span: self.span_from(start),
         })
     }
 
+    pub fn parse_identifier(&self) -> Expression<'a> {
+        Expression::Identifier(self.curr_identifier())
+    }
+
     pub fn parse_integer_literal(&mut self) -> Option<Expression<'a>> {
         let literal = self.curr_token.literal.parse::<i64>().ok().or_else(|| {
-            self.errors.push(format!(
-                "Could not parse {} as integer",
-                self.curr_token.literal
-            ));
+            let message = format!("Could not parse {} as integer", self.curr_token.literal);
+            self.errors
+                .push(ParseError::new(message, &self.curr_token, None));
             None
         })?;
 
         Some(Expression::Integer {
-            token: self.curr_token,
+            token: self.curr_token.clone(),
             value: literal,
+            span: Some(self.curr_span),
         })
     }
 
+    pub fn parse_float_literal(&mut self) -> Option<Expression<'a>> {
+        let literal = self.curr_token.literal.parse::<f64>().ok().or_else(|| {
+            let message = format!("Could not parse {} as float", self.curr_token.literal);
+            self.errors
+                .push(ParseError::new(message, &self.curr_token, None));
+            None
+        })?;
+
+        Some(Expression::Float {
+            token: self.curr_token.clone(),
+            value: literal,
+            span: Some(self.curr_span),
+        })
+    }
+
+    pub fn parse_string_literal(&self) -> Expression<'a> {
+        Expression::StringLiteral {
+            token: self.curr_token.clone(),
+            value: unescape(&self.curr_token.literal),
+            span: Some(self.curr_span),
+        }
+    }
+
+    pub fn parse_null_literal(&self) -> Expression<'a> {
+        Expression::NullLiteral {
+            token: self.curr_token.clone(),
+            span: Some(self.curr_span),
+        }
+    }
+
     pub fn parse_expression_statement(&mut self) -> Option<Statement<'a>> {
         let expression = self.parse_expression_w_precedence(Precedence::Lowest)?;
 
@@ -241,26 +579,48 @@ impl<'a> Parser<'a> {
         &mut self,
         prededence: Precedence,
     ) -> Option<Expression<'a>> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            self.too_deep_error();
+            return None;
+        }
+
+        self.depth += 1;
+        let expression = crate::grow_stack(|| self.parse_expression_on_stack(prededence));
+        self.depth -= 1;
+        expression
+    }
+
+    // Reports the nesting limit once and skips the rest of the input, which
+    // would otherwise report it again for every enclosing operator.
+    fn too_deep_error(&mut self) {
+        let message = format!(
+            "expression nested more than {} levels deep",
+            MAX_NESTING_DEPTH
+        );
+        self.errors
+            .push(ParseError::new(message, &self.curr_token, None));
+        while !self.peek_token_is(TokenType::Eof) {
+            self.next_token();
+        }
+    }
+
+    fn parse_expression_on_stack(&mut self, prededence: Precedence) -> Option<Expression<'a>> {
         let mut left = self.parse_expression_prefix()?;
 
         while !self.peek_token_is(TokenType::Semicolon) && prededence < self.peek_precedence() {
             self.next_token();
-            if let Some(infix) = self.parse_expression_infix(left.clone()) {
-                left = infix
-            }
+            // A failed infix has already reported its error; the loop carries
+            // on with a stand-in so later operators still report theirs.
+            let token = self.curr_token.clone();
+            left = self
+                .parse_expression_infix(left)
+                .unwrap_or(Expression::NullLiteral { token, span: None });
         }
         Some(left)
     }
 
-    fn get_precedence_of_token(token_type: TokenType) -> Precedence {
-        match token_type {
-            TokenType::Eq | TokenType::NotEq => Precedence::Equals,
-            TokenType::Lt | TokenType::Gt => Precedence::LessGreater,
-            TokenType::Plus | TokenType::Minus => Precedence::Sum,
-            TokenType::Asterisk | TokenType::Slash => Precedence::Product,
-            TokenType::LParen => Precedence::Call,
-            _ => Precedence::Lowest,
-        }
+    pub(crate) fn get_precedence_of_token(token_type: TokenType) -> Precedence {
+        Precedence::of(token_type)
     }
 
     pub fn peek_precedence(&self) -> Precedence {
@@ -284,7 +644,8 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_if_expression(&mut self) -> Option<Expression<'a>> {
-        let token = self.curr_token;
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
 
         if !self.expect_peek(TokenType::LParen) {
             return None;
@@ -318,22 +679,44 @@ impl<'a> Parser<'a> {
             condition,
             consequence,
             alternative,
+            span: self.span_from(start),
         })
     }
 
     fn parse_block_statement(&mut self) -> Statement<'a> {
         let mut statements: Vec<Statement> = Vec::new();
-        let token = self.curr_token;
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
+
+        self.next_token();
 
         while !self.curr_token_is(TokenType::RBrace) && !self.curr_token_is(TokenType::Eof) {
-            let stmt = self.parse_statement();
-            if let Some(x) = stmt {
-                statements.push(x)
-            };
+            match self.parse_statement() {
+                Some(stmt) => statements.push(stmt),
+                None => self.synchronize(),
+            }
             self.next_token();
         }
 
-        Statement::Block { token, statements }
+        // Running out of input before the closing brace leaves the block open
+        if self.curr_token_is(TokenType::Eof) {
+            let message = format!(
+                "expected next token to be {:?}, got {:?} instead",
+                TokenType::RBrace,
+                TokenType::Eof
+            );
+            self.errors.push(ParseError::new(
+                message,
+                &self.curr_token,
+                Some(TokenType::RBrace),
+            ));
+        }
+
+        Statement::Block {
+            token,
+            statements,
+            span: self.span_from(start),
+        }
     }
 
     fn parse_function_parameters(&mut self) -> Option<Vec<Identifier<'a>>> {
@@ -346,18 +729,16 @@ impl<'a> Parser<'a> {
 
         self.next_token();
 
-        identifiers.push(Identifier {
-            token: self.curr_token,
-            value: self.curr_token.literal,
-        });
+        identifiers.push(self.curr_identifier());
 
         while self.peek_token_is(TokenType::Comma) {
             self.next_token();
+            // Tolerate a trailing comma before the closing parenthesis
+            if self.peek_token_is(TokenType::RParen) {
+                break;
+            }
             self.next_token();
-            identifiers.push(Identifier {
-                token: self.curr_token,
-                value: self.curr_token.literal,
-            });
+            identifiers.push(self.curr_identifier());
         }
 
         if !self.expect_peek(TokenType::RParen) {
@@ -368,47 +749,164 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_call_expression(&mut self, expression: Expression<'a>) -> Option<Expression<'a>> {
-        let token = self.curr_token;
+        let token = self.curr_token.clone();
+        let start = expression
+            .span()
+            .map_or(self.curr_span.0, |(start, _)| start);
         let function = Box::new(expression);
 
-        let arguments = self.parse_call_arguments();
+        let arguments = self.parse_call_arguments()?;
+
+        Some(Expression::Call {
+            token,
+            function,
+            arguments,
+            span: self.span_from(start),
+        })
+    }
+
+    // `receiver.name(args)` is sugar for `name(receiver, args)`
+    fn parse_method_call(&mut self, receiver: Expression<'a>) -> Option<Expression<'a>> {
+        let start = receiver.span().map_or(self.curr_span.0, |(start, _)| start);
+        if !self.expect_peek(TokenType::Ident) {
+            return None;
+        }
+        let function = Box::new(self.parse_identifier());
+
+        if !self.expect_peek(TokenType::LParen) {
+            return None;
+        }
+        let token = self.curr_token.clone();
+
+        let mut arguments = vec![receiver];
+        arguments.extend(self.parse_call_arguments()?);
 
         Some(Expression::Call {
             token,
             function,
             arguments,
+            span: self.span_from(start),
         })
     }
 
     fn parse_call_arguments(&mut self) -> Option<Vec<Expression<'a>>> {
-        let mut args: Vec<Expression> = vec![];
+        self.parse_expression_list(TokenType::RParen)
+    }
+
+    fn parse_index_expression(&mut self, left: Expression<'a>) -> Option<Expression<'a>> {
+        let token = self.curr_token.clone();
+        let start = left.span().map_or(self.curr_span.0, |(start, _)| start);
+        let left = Box::new(left);
+
+        self.next_token();
+        let index = Box::new(self.parse_expression_w_precedence(Precedence::Lowest)?);
+
+        if !self.expect_peek(TokenType::RBracket) {
+            return None;
+        }
+
+        Some(Expression::Index {
+            token,
+            left,
+            index,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_array_literal(&mut self) -> Option<Expression<'a>> {
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
+        let elements = self.parse_expression_list(TokenType::RBracket)?;
+
+        Some(Expression::ArrayLiteral {
+            token,
+            elements,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_hash_literal(&mut self) -> Option<Expression<'a>> {
+        let token = self.curr_token.clone();
+        let start = self.curr_span.0;
+        let mut pairs = vec![];
+
+        while !self.peek_token_is(TokenType::RBrace) {
+            self.next_token();
+            let key = self.parse_expression_w_precedence(Precedence::Lowest)?;
+
+            if !self.expect_peek(TokenType::Colon) {
+                return None;
+            }
 
-        if self.peek_token_is(TokenType::RParen) {
             self.next_token();
+            let value = self.parse_expression_w_precedence(Precedence::Lowest)?;
+            pairs.push((key, value));
+
+            if !self.peek_token_is(TokenType::RBrace) && !self.expect_peek(TokenType::Comma) {
+                return None;
+            }
+        }
+
+        if !self.expect_peek(TokenType::RBrace) {
             return None;
         }
 
-        self.next_token();
+        Some(Expression::HashLiteral {
+            token,
+            pairs,
+            span: self.span_from(start),
+        })
+    }
 
-        args.push(self.parse_expression_w_precedence(Precedence::Lowest)?);
+    fn parse_expression_list(&mut self, end: TokenType) -> Option<Vec<Expression<'a>>> {
+        let mut list: Vec<Expression> = vec![];
+
+        if self.peek_token_is(end) {
+            self.next_token();
+            return Some(list);
+        }
+
+        self.next_token();
+        list.push(self.parse_expression_w_precedence(Precedence::Lowest)?);
 
         while self.peek_token_is(TokenType::Comma) {
             self.next_token();
+            // Tolerate a trailing comma before the closing token
+            if self.peek_token_is(end) {
+                break;
+            }
             self.next_token();
-            args.push(self.parse_expression_w_precedence(Precedence::Lowest)?);
+            list.push(self.parse_expression_w_precedence(Precedence::Lowest)?);
         }
 
-        if !self.expect_peek(TokenType::RParen) {
+        if !self.expect_peek(end) {
             return None;
         }
 
-        Some(args)
+        Some(list)
     }
 
     fn parse_boolean_expression(&self) -> Option<Expression<'a>> {
         Some(Expression::Boolean {
-            token: self.curr_token,
+            token: self.curr_token.clone(),
             value: self.curr_token_is(TokenType::True),
+            span: Some(self.curr_span),
         })
     }
 }
+
+/// Parses `input` and renders each statement on its own line using the
+/// fully parenthesized `Display` form, which makes precedence visible.
+pub fn dump_ast(input: &str) -> Result<String, Vec<ParseError>> {
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+
+    match program {
+        Some(program) if parser.errors.is_empty() => Ok(program
+            .statements
+            .iter()
+            .map(|statement| format!("{}\n", statement))
+            .collect()),
+        _ => Err(parser.errors),
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
-    ast::ast::{Expression, Statement},
-    lexer::lexer::Lexer,
-    parser::parser::Parser,
+    ast::{
+        ast::{Expression, Statement},
+        span::Node,
+        visitor::{Visitor, walk_expression, walk_program},
+    },
+    lexer::{lexer::Lexer, token::TokenType},
+    parser::{ParseError, dump_ast, format_errors, parser::Parser},
 };
 
 #[test]
@@ -145,6 +149,7 @@ fn test_prefix_plus_and_minus() {
                 token,
                 operator,
                 right,
+                ..
             } = expression
             {
                 println!("{}, {}, {:?}", token, operator, right);
@@ -454,6 +459,10 @@ fn test_let_and_return_statements() {
         "let y = false",
         "return true",
         "return 1 / 2",
+        "let x = 2 + 2",
+        "return f(a, b * c)",
+        "let xs = [1, -y][0]",
+        "return a ? b : c",
     ];
     let expected = [
         "return (x + 2);",
@@ -461,6 +470,10 @@ fn test_let_and_return_statements() {
         "let y = false;",
         "return true;",
         "return (1 / 2);",
+        "let x = (2 + 2);",
+        "return f(a, (b * c));",
+        "let xs = ([1, -y][0]);",
+        "return (a ? b : c);",
     ];
     let input_string = input.join(";");
 
@@ -484,3 +497,1023 @@ fn test_let_and_return_statements() {
         assert_eq!(expression.to_string(), expected_output.to_string())
     }
 }
+
+#[test]
+fn test_string_literal_expression() {
+    let input = r#""hello world";"#;
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+    assert_eq!(program.statements.len(), 1);
+
+    match &program.statements[0] {
+        Statement::Expression(Expression::StringLiteral { value, .. }) => {
+            assert_eq!(*value, "hello world")
+        }
+        other => panic!("stmt not a string literal. got={:?}", other),
+    }
+}
+
+#[test]
+fn test_array_literal_expression() {
+    let input = ["[1, 2 * 2, 3 + 3]", "[]", "[1, 2,]"];
+    let expected = ["[1, (2 * 2), (3 + 3)]", "[]", "[1, 2]"];
+
+    for (input, expected) in input.iter().zip(expected) {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+        assert_eq!(program.statements.len(), 1);
+
+        match &program.statements[0] {
+            Statement::Expression(expression @ Expression::ArrayLiteral { .. }) => {
+                assert_eq!(expression.to_string(), expected)
+            }
+            other => panic!("stmt not an array literal. got={:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_index_expression() {
+    let input = [
+        "myArray[1 + 1]",
+        "a * [1, 2, 3, 4][b * c] * d",
+        "add(a * b[2], b[1], 2 * [1, 2][1])",
+    ];
+    let expected = [
+        "(myArray[(1 + 1)])",
+        "((a * ([1, 2, 3, 4][(b * c)])) * d)",
+        "add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))",
+    ];
+
+    for (input, expected) in input.iter().zip(expected) {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+        assert_eq!(program.statements.len(), 1);
+        assert_eq!(program.statements[0].to_string(), expected)
+    }
+}
+
+#[test]
+fn test_hash_literal_expression() {
+    let input = [
+        r#"{"one": 1, "two": 2, "three": 3}"#,
+        "{}",
+        r#"{"one": 0 + 1, "two": 10 - 8}"#,
+    ];
+    let expected = [
+        "{one: 1, two: 2, three: 3}",
+        "{}",
+        "{one: (0 + 1), two: (10 - 8)}",
+    ];
+
+    for (input, expected) in input.iter().zip(expected) {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+        assert_eq!(program.statements.len(), 1);
+
+        match &program.statements[0] {
+            Statement::Expression(expression @ Expression::HashLiteral { .. }) => {
+                assert_eq!(expression.to_string(), expected)
+            }
+            other => panic!("stmt not a hash literal. got={:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_parse_if_else_alternative() {
+    let input = "if (x < 2) { 1 } else { 2 }";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+    assert_eq!(program.statements.len(), 1);
+
+    let Statement::Expression(Expression::If {
+        consequence,
+        alternative,
+        ..
+    }) = &program.statements[0]
+    else {
+        panic!("stmt not an if expression. got={:?}", program.statements[0]);
+    };
+
+    assert_eq!(consequence.to_string(), "1");
+    let alternative = alternative.as_ref().expect("alternative was not parsed");
+    match alternative.as_ref() {
+        Statement::Block { statements, .. } => {
+            assert_eq!(statements.len(), 1);
+            assert_eq!(statements[0].to_string(), "2");
+        }
+        other => panic!("alternative not a block statement. got={:?}", other),
+    }
+    assert_eq!(
+        program.statements[0].to_string(),
+        "if (x < 2) { 1 } else { 2 }"
+    );
+}
+
+#[test]
+fn test_parse_if_else_requires_block() {
+    let input = "if (x < 2) { 1 } else 2";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    parser.parse_program();
+
+    assert_eq!(
+        parser
+            .errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["expected next token to be LBrace, got Int instead at line 1, col 23"]
+    );
+}
+
+#[test]
+fn test_assign_statements() {
+    let input = "x = 10; y = x + 1";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+    assert_eq!(program.statements.len(), 2);
+
+    let expected = [("x", "x = 10;"), ("y", "y = (x + 1);")];
+    for (stmt, (expected_name, expected_output)) in program.statements.iter().zip(expected) {
+        if let Statement::Assign { name, .. } = stmt {
+            assert_eq!(name.value, expected_name);
+        } else {
+            panic!("stmt not an AssignStatement. got={:?}", stmt);
+        }
+        assert_eq!(stmt.to_string(), expected_output);
+    }
+}
+
+#[test]
+fn test_parse_error_location() {
+    let input = "let x = 1;\nlet y = 2;\nlet z 3;";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    parser.parse_program();
+
+    assert_eq!(
+        parser.errors().first().map(ToString::to_string).as_deref(),
+        Some("expected next token to be Assign, got Int instead at line 3, col 7")
+    );
+}
+
+#[test]
+fn test_float_literal_expression() {
+    let input = "2.75; 1 + 2.5";
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+    assert_eq!(program.statements.len(), 2);
+
+    match &program.statements[0] {
+        Statement::Expression(Expression::Float { value, .. }) => assert_eq!(*value, 2.75),
+        other => panic!("stmt not a float literal. got={:?}", other),
+    }
+    assert_eq!(program.statements[1].to_string(), "(1 + 2.5)");
+}
+
+#[test]
+fn test_string_literal_escapes() {
+    let input = r#""tab\there\n""#;
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+
+    match &program.statements[0] {
+        Statement::Expression(Expression::StringLiteral { value, .. }) => {
+            assert_eq!(value, "tab\there\n")
+        }
+        other => panic!("stmt not a string literal. got={:?}", other),
+    }
+}
+
+#[test]
+fn test_nesting_depth_limit() {
+    let depth = crate::parser::parser::MAX_NESTING_DEPTH;
+
+    // The statement's own expression is one level, each parenthesis another
+    let input = format!("{}1{};", "(".repeat(depth - 1), ")".repeat(depth - 1));
+    let mut parser = Parser::new(Lexer::new(&input));
+    parser.parse_program();
+    assert!(parser.errors().is_empty());
+
+    let input = format!("{}1{}; let x = 1;", "(".repeat(depth), ")".repeat(depth));
+    let mut parser = Parser::new(Lexer::new(&input));
+    parser.parse_program();
+    assert_eq!(
+        parser.errors(),
+        [ParseError {
+            message: format!("expression nested more than {} levels deep", depth),
+            line: 1,
+            column: depth + 1,
+            expected: None,
+            got: TokenType::Int,
+        }]
+    );
+}
+
+#[test]
+fn test_unterminated_string_error() {
+    let input = "let a = 1;\nlet s = \"hello";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    parser.parse_program();
+
+    assert!(!parser.errors().is_empty());
+    assert_eq!(
+        parser.errors()[0].to_string(),
+        "unterminated string literal at line 2, col 9"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_program_json_round_trip() {
+    let inputs = [
+        "let x = 2 + 3;",
+        r#"let f = fn(a, b) { if (a < b) { return -a; } else { "a\nb" } }; f(1, [2][0]);"#,
+    ];
+
+    for input in inputs {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+
+        let json = program.to_json().expect("program failed to serialize");
+        let decoded = crate::ast::Program::from_json(&json).expect("program failed to deserialize");
+
+        assert_eq!(decoded, program);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_program_json_rejects_escaped_identifiers() {
+    let program = Parser::new(Lexer::new("let x = 1;"))
+        .parse_program()
+        .expect("parse_program() returned None");
+    let json = program.to_json().expect("program failed to serialize");
+    let escaped = json.replace(r#""value":"x""#, r#""value":"\u0078""#);
+    assert_ne!(escaped, json);
+
+    assert!(crate::ast::Program::from_json(&escaped).is_err());
+}
+
+#[test]
+fn test_program_format() {
+    let tests = [
+        ("let   x=5", "let x = 5;\n"),
+        ("x = y;", "x = y;\n"),
+        ("(5 * 5) * 2;", "5 * 5 * 2;\n"),
+        ("5 * (5 + 2);", "5 * (5 + 2);\n"),
+        ("a - (b - c);", "a - (b - c);\n"),
+        ("-(a + b);", "-(a + b);\n"),
+        ("a ** (b ** c);", "a ** b ** c;\n"),
+        ("(a ** b) ** c;", "(a ** b) ** c;\n"),
+        ("a ? b : (c ? d : e);", "a ? b : c ? d : e;\n"),
+        ("(a ? b : c) ? d : e;", "(a ? b : c) ? d : e;\n"),
+        ("(a ? b : c) + 1;", "(a ? b : c) + 1;\n"),
+        ("add(1,2)[0];", "add(1, 2)[0];\n"),
+        (r#"{"a\n":[1,2.5]}"#, "{\"a\\n\": [1, 2.5]};\n"),
+        ("fn() {}();", "fn() {}();\n"),
+        (
+            "let max = fn(a, b) { if (a > b) { if (a > 10) { return 10; } a } else { let c = fn(x) { x * 2 }; c(b) } };",
+            "let max = fn(a, b) {
+    if (a > b) {
+        if (a > 10) {
+            return 10;
+        }
+        a;
+    } else {
+        let c = fn(x) {
+            x * 2;
+        };
+        c(b);
+    }
+};
+",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+
+        let formatted = program.format();
+        assert_eq!(formatted, expected);
+
+        let lexer = Lexer::new(&formatted);
+        let mut parser = Parser::new(lexer);
+        let reparsed = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(
+            parser.errors().is_empty(),
+            "Errors while parsing {}",
+            formatted
+        );
+        assert_eq!(reparsed.format(), formatted);
+    }
+}
+
+#[test]
+fn test_visitor_collects_integers() {
+    struct IntegerCollector(Vec<i64>);
+
+    impl<'a> Visitor<'a> for IntegerCollector {
+        fn visit_expression(&mut self, expression: &Expression<'a>) {
+            if let Expression::Integer { value, .. } = expression {
+                self.0.push(*value);
+            }
+            walk_expression(self, expression);
+        }
+    }
+
+    let input = "
+        let x = 1 + 2 * -3;
+        let f = fn(a) { if (a > 4) { return [5, a][6]; } else { {7: 8} } };
+        f(9, x);
+        ";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+
+    let mut collector = IntegerCollector(Vec::new());
+    walk_program(&mut collector, &program);
+
+    assert_eq!(collector.0, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_null_literal_expression() {
+    let input = "null; null == x";
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+    assert_eq!(program.statements.len(), 2);
+
+    match &program.statements[0] {
+        Statement::Expression(Expression::NullLiteral { token, .. }) => {
+            assert_eq!(token.literal, "null")
+        }
+        other => panic!("stmt not a null literal. got={:?}", other),
+    }
+    assert_eq!(program.statements[1].to_string(), "(null == x)");
+}
+
+#[test]
+fn test_prefix_plus_display() {
+    let tests = [
+        ("+5 * 2", "(+5 * 2)"),
+        ("-+a", "-+a"),
+        ("1 - +2.5", "(1 - +2.5)"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
+#[test]
+fn test_parser_recovers_after_errors() {
+    let input = "let = 5 + 1; let x 10; let y = 1;";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+
+    assert_eq!(
+        parser
+            .errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            "expected next token to be Ident, got Assign instead at line 1, col 5",
+            "expected next token to be Assign, got Int instead at line 1, col 20",
+        ]
+    );
+    assert_eq!(program.statements.len(), 1);
+    assert_eq!(program.statements[0].to_string(), "let y = 1;");
+}
+
+#[test]
+fn test_parser_reports_missing_prefix() {
+    let input = "let f = fn() { let; 1 }; ) ; f();";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+
+    assert_eq!(
+        parser
+            .errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            "expected next token to be Ident, got Semicolon instead at line 1, col 19",
+            "no prefix parse function for RParen found at line 1, col 26",
+        ]
+    );
+    assert_eq!(program.statements.len(), 2);
+    assert_eq!(program.statements[1].to_string(), "f()");
+}
+
+#[test]
+fn test_macro_literal_parsing() {
+    let input = "macro(x, y) { x + y; }";
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+    assert_eq!(program.statements.len(), 1);
+
+    match &program.statements[0] {
+        Statement::Expression(Expression::MacroLiteral { parameters, .. }) => {
+            assert_eq!(
+                parameters.iter().map(|p| p.value).collect::<Vec<_>>(),
+                ["x", "y"]
+            );
+        }
+        other => panic!("stmt not a macro literal. got={:?}", other),
+    }
+    assert_eq!(program.statements[0].to_string(), "macro(x, y) { (x + y) }");
+    assert_eq!(program.format(), "macro(x, y) {\n    x + y;\n};\n");
+}
+
+#[test]
+fn test_function_declaration_desugars_to_let() {
+    let tests = [
+        ("fn add(x, y) { x + y }", "let add = fn(x, y) { x + y };"),
+        ("fn answer() { 42 };", "let answer = fn() { 42 };"),
+    ];
+
+    for (declaration, let_form) in tests {
+        let lexer = Lexer::new(declaration);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+        assert_eq!(program.statements.len(), 1);
+        assert!(matches!(program.statements[0], Statement::Let { .. }));
+
+        let lexer = Lexer::new(let_form);
+        let mut parser = Parser::new(lexer);
+        let expected = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+
+        assert_eq!(
+            program.statements[0].to_string(),
+            expected.statements[0].to_string()
+        );
+    }
+}
+
+#[test]
+fn test_parse_error_fields() {
+    let lexer = Lexer::new("let 5 = 5;");
+    let mut parser = Parser::new(lexer);
+    parser.parse_program();
+
+    let errors = parser.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0],
+        ParseError {
+            message: "expected next token to be Ident, got Int instead".to_string(),
+            line: 1,
+            column: 5,
+            expected: Some(TokenType::Ident),
+            got: TokenType::Int,
+        }
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "expected next token to be Ident, got Int instead at line 1, col 5"
+    );
+}
+
+#[test]
+fn test_call_argument_counts() {
+    let tests = [
+        ("foo()", 0, "foo()"),
+        ("foo(1)", 1, "foo(1)"),
+        ("foo(1, a * 2)", 2, "foo(1, (a * 2))"),
+    ];
+
+    for (input, expected_count, expected_display) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing");
+
+        match &program.statements[0] {
+            Statement::Expression(Expression::Call { arguments, .. }) => {
+                assert_eq!(arguments.len(), expected_count, "input: {}", input)
+            }
+            other => panic!("stmt not a call expression. got={:?}", other),
+        }
+        assert_eq!(program.statements[0].to_string(), expected_display);
+    }
+}
+
+#[test]
+fn test_trailing_commas() {
+    let tests = [
+        ("add(1, 2,)", "add(1, 2)"),
+        ("fn(x,) { x }", "fn (x) { x }"),
+        ("fn(x, y,) { x + y }", "fn (x , y) { (x + y) }"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(program.statements.len(), 1);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
+#[test]
+fn test_dump_ast() {
+    assert_eq!(dump_ast("let x = 2 + 3;").unwrap(), "let x = (2 + 3);\n");
+    assert_eq!(
+        dump_ast("let x = 1;\nx * 2 + 1").unwrap(),
+        "let x = 1;\n((x * 2) + 1)\n"
+    );
+
+    let errors = dump_ast("let x 5;").unwrap_err();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_power_precedence() {
+    let tests = [
+        ("2 ** 3", "(2 ** 3)"),
+        ("2 ** 3 ** 2", "(2 ** (3 ** 2))"),
+        ("2 * 3 ** 2", "(2 * (3 ** 2))"),
+        ("2 ** 3 * 2", "((2 ** 3) * 2)"),
+        ("a ** b ** c - d", "((a ** (b ** c)) - d)"),
+        ("(2 ** 3) ** 2", "((2 ** 3) ** 2)"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(
+            program.statements[0].to_string(),
+            expected,
+            "input: {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_method_call_desugars_to_call() {
+    let tests = [
+        ("x.len()", "len(x)"),
+        (r#""a,b".split(",")"#, r#"split("a,b", ",")"#),
+        ("[1, 2].push(3).len()", "len(push([1, 2], 3))"),
+        ("a[0].first()", "first(a[0])"),
+        ("x.len() + 1", "len(x) + 1"),
+        ("-x.len()", "-len(x)"),
+        ("5.type()", "type(5)"),
+    ];
+
+    for (input, desugared) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+
+        let mut expected_parser = Parser::new(Lexer::new(desugared));
+        let expected = expected_parser
+            .parse_program()
+            .expect("parse_program() returned None");
+
+        assert_eq!(
+            program.statements[0].to_string(),
+            expected.statements[0].to_string(),
+            "input: {}",
+            input
+        );
+        assert_eq!(program.format(), expected.format(), "input: {}", input);
+    }
+}
+
+#[test]
+fn test_method_call_errors() {
+    let tests = [
+        ("x.1()", "expected next token to be Ident, got Int instead"),
+        ("x.len", "expected next token to be LParen, got Eof instead"),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        parser.parse_program();
+
+        let errors = parser.errors();
+        assert!(!errors.is_empty(), "expected errors for {}", input);
+        assert_eq!(errors[0].message, expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_format_errors() {
+    let source = "let x 5;\nlet = 10;";
+    let mut parser = Parser::new(Lexer::new(source));
+    parser.parse_program();
+
+    let errors = parser.errors();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        format_errors(&errors, source),
+        "Woops! We ran into some monkey business here!
+ parser errors:
+error: expected next token to be Assign, got Int instead
+ --> 1:7
+  |
+1 | let x 5;
+  |       ^
+error: expected next token to be Ident, got Assign instead
+ --> 2:5
+  |
+2 | let = 10;
+  |     ^
+"
+    );
+}
+
+#[test]
+fn test_valueless_return() {
+    let tests = [
+        ("return;", 1, "return;"),
+        ("return", 1, "return;"),
+        ("return; 10;", 2, "return;"),
+    ];
+
+    for (input, statement_count, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(
+            program.statements.len(),
+            statement_count,
+            "input: {}",
+            input
+        );
+        match &program.statements[0] {
+            Statement::Return { value, .. } => assert_eq!(*value, Expression::None),
+            other => panic!("expected a return statement, got {:?}", other),
+        }
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
+#[test]
+fn test_minimum_integer_literal() {
+    let mut parser = Parser::new(Lexer::new("-9223372036854775808"));
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors().is_empty(), "Errors while parsing");
+
+    match &program.statements[0] {
+        Statement::Expression(Expression::Integer { value, .. }) => assert_eq!(*value, i64::MIN),
+        other => panic!("expected an integer literal, got {:?}", other),
+    }
+    assert_eq!(program.format(), "-9223372036854775808;\n");
+
+    let mut parser = Parser::new(Lexer::new("9223372036854775808"));
+    parser.parse_program();
+    assert_eq!(
+        parser.errors()[0].message,
+        "Could not parse 9223372036854775808 as integer"
+    );
+}
+
+#[test]
+fn test_empty_program_and_blocks() {
+    for input in ["", "   \n\t", ";;"] {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "input: {:?}", input);
+        assert!(program.statements.is_empty(), "input: {:?}", input);
+    }
+
+    let mut parser = Parser::new(Lexer::new("fn() {}"));
+    let program = parser.parse_program().unwrap();
+    assert!(parser.errors().is_empty());
+    match &program.statements[0] {
+        Statement::Expression(Expression::Function { body, .. }) => match body.as_ref() {
+            Statement::Block { statements, .. } => assert!(statements.is_empty()),
+            other => panic!("expected a block body, got {:?}", other),
+        },
+        other => panic!("expected a function literal, got {:?}", other),
+    }
+
+    let mut parser = Parser::new(Lexer::new("if (true) {} else {}"));
+    let program = parser.parse_program().unwrap();
+    assert!(parser.errors().is_empty());
+    assert_eq!(program.statements.len(), 1);
+}
+
+#[test]
+fn test_unclosed_block_is_an_error() {
+    let tests = [
+        ("let f = fn() {", 1, 15),
+        ("if (x) { 1", 1, 11),
+        ("fn() {\n  let x = 1;\n", 3, 1),
+    ];
+
+    for (input, line, column) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        parser.parse_program();
+
+        let errors = parser.errors();
+        assert_eq!(errors.len(), 1, "input: {:?} errors: {:?}", input, errors);
+        assert_eq!(
+            errors[0].message,
+            "expected next token to be RBrace, got Eof instead"
+        );
+        assert_eq!((errors[0].line, errors[0].column), (line, column));
+    }
+}
+
+#[test]
+fn test_parser_skips_preserved_comments() {
+    let input = "// leading\nlet x = /* inline */ 5; // trailing\nx";
+    let mut parser = Parser::new(Lexer::new(input).preserve_comments(true));
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+
+    assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+    assert_eq!(program.format(), "let x = 5;\nx;\n");
+}
+
+#[test]
+fn test_left_associativity() {
+    let tests = [
+        ("10 - 5 - 2", "((10 - 5) - 2)"),
+        ("100 / 5 / 2", "((100 / 5) / 2)"),
+        ("100 % 7 % 3", "((100 % 7) % 3)"),
+        ("10 - 5 + 2", "((10 - 5) + 2)"),
+        ("10 / 5 * 2", "((10 / 5) * 2)"),
+        ("a == b != c", "((a == b) != c)"),
+        ("a < b <= c", "((a < b) <= c)"),
+        ("a << b >> c", "((a << b) >> c)"),
+        ("a & b & c", "((a & b) & c)"),
+        ("x[0][1]", "((x[0])[1])"),
+        ("f(1)(2)", "f(1)(2)"),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(
+            program.statements[0].to_string(),
+            expected,
+            "input: {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_bare_block_statement() {
+    let tests = [
+        ("{ let x = 5; x; }", true, 2),
+        ("{ x }", true, 1),
+        ("{ f(1); { 2 } }", true, 2),
+        (r#"{ {"a": 1}["a"] }"#, true, 1),
+        (r#"{"a": 1}"#, false, 0),
+        ("{}", false, 0),
+        ("{[1, 2][0]: true}", false, 0),
+        ("{ x ? 1 : 2 }", true, 1),
+        ("{ x ? 1 : 2; y }", true, 2),
+        (r#"{ x ? "a" : "b": 1 }"#, false, 0),
+        (r#"{"a": x ? 1 : 2}"#, false, 0),
+    ];
+
+    for (input, is_block, statement_count) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(program.statements.len(), 1, "input: {}", input);
+
+        match &program.statements[0] {
+            Statement::Block { statements, .. } => {
+                assert!(is_block, "{} parsed as a block", input);
+                assert_eq!(statements.len(), statement_count, "input: {}", input);
+            }
+            Statement::Expression(Expression::HashLiteral { .. }) => {
+                assert!(!is_block, "{} parsed as a hash", input)
+            }
+            other => panic!("unexpected statement for {}: {:?}", input, other),
+        }
+    }
+
+    let mut parser = Parser::new(Lexer::new("{ let x = 5; x }"));
+    let program = parser.parse_program().unwrap();
+    assert_eq!(program.format(), "{\n    let x = 5;\n    x;\n}\n");
+}
+
+#[test]
+fn test_node_spans() {
+    let input = "let x = add(y, 2) * [1, 2][0];";
+    let program = Parser::new(Lexer::new(input)).parse_program().unwrap();
+
+    let Statement::Let {
+        name, value, span, ..
+    } = &program.statements[0]
+    else {
+        panic!("expected a let statement");
+    };
+    let text = |span: Option<(usize, usize)>| {
+        let (start, end) = span.unwrap();
+        &input[start..end]
+    };
+
+    assert_eq!(text(*span), input);
+    assert_eq!(text(name.span), "x");
+    assert_eq!(text(value.span()), "add(y, 2) * [1, 2][0]");
+
+    let Expression::Infix { left, right, .. } = value else {
+        panic!("expected an infix expression");
+    };
+    assert_eq!(text(left.span()), "add(y, 2)");
+    assert_eq!(text(right.span()), "[1, 2][0]");
+}
+
+#[test]
+fn test_node_at() {
+    let input = "let x = y + 1;";
+    let program = Parser::new(Lexer::new(input)).parse_program().unwrap();
+
+    let Some(Node::Expression(Expression::Identifier(identifier))) = program.node_at(8) else {
+        panic!("expected the identifier y, got {:?}", program.node_at(8));
+    };
+    assert_eq!(identifier.value, "y");
+
+    assert!(matches!(
+        program.node_at(10),
+        Some(Node::Expression(Expression::Infix { .. }))
+    ));
+    assert!(matches!(program.node_at(4), Some(Node::Identifier(name)) if name.value == "x"));
+    assert!(matches!(
+        program.node_at(0),
+        Some(Node::Statement(Statement::Let { .. }))
+    ));
+    assert_eq!(program.node_at(input.len()), None);
+}
+
+#[test]
+fn test_bitwise_operator_precedence() {
+    let tests = [
+        ("a | b ^ c & d", "(a | (b ^ (c & d)))"),
+        ("a & b ^ c | d", "(((a & b) ^ c) | d)"),
+        ("a & b << c", "(a & (b << c))"),
+        ("a << b + c", "(a << (b + c))"),
+        ("a + b >> c * d", "((a + b) >> (c * d))"),
+        ("a | b == c", "((a | b) == c)"),
+        ("a < b & c", "(a < (b & c))"),
+        ("-a << b", "(-a << b)"),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(
+            program.statements[0].to_string(),
+            expected,
+            "input: {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_ternary_expression() {
+    let tests = [
+        ("x > 0 ? pos : neg", "((x > 0) ? pos : neg)"),
+        ("a ? b : c ? d : e", "(a ? b : (c ? d : e))"),
+        ("a ? b ? c : d : e", "(a ? (b ? c : d) : e)"),
+        ("a == b ? c + 1 : d * 2", "((a == b) ? (c + 1) : (d * 2))"),
+        ("(a ? b : c) + 1", "((a ? b : c) + 1)"),
+        ("f(a ? b : c, d)", "f((a ? b : c), d)"),
+        ("let x = y ? 1 : 2;", "let x = (y ? 1 : 2);"),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        assert!(parser.errors().is_empty(), "Errors while parsing {}", input);
+        assert_eq!(
+            program.statements[0].to_string(),
+            expected,
+            "input: {}",
+            input
+        );
+    }
+
+    let mut parser = Parser::new(Lexer::new("a ? b c"));
+    parser.parse_program();
+    assert_eq!(
+        parser.errors()[0].message,
+        "expected next token to be Colon, got Ident instead"
+    );
+}
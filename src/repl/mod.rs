@@ -0,0 +1,5 @@
+pub mod repl;
+pub use repl::{CommandOutcome, Sources, is_incomplete, new_environment, run_command, start};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,196 @@
+use std::io::{self, BufRead, Write};
+
+use typed_arena::Arena;
+
+use crate::{
+    Environment, Lexer, Parser, eval_program_with_output, lexer::TokenType, parser::format_errors,
+    with_prelude,
+};
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = "... ";
+
+/// Owns every input evaluated since the session started or was last reset.
+/// Bindings borrow from the source they were parsed from, so the environment
+/// can't outlive it.
+pub type Sources = Arena<String>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CommandOutcome {
+    Continue,
+    /// The environment was cleared, so the inputs kept for it can be dropped.
+    Reset,
+    Quit,
+}
+
+pub fn run_command<'src>(
+    command: &str,
+    env: &mut Environment<'src>,
+    sources: &'src Sources,
+    output: &mut dyn Write,
+) -> io::Result<CommandOutcome> {
+    match command {
+        ":quit" => return Ok(CommandOutcome::Quit),
+        ":reset" => {
+            env.clear();
+            *env = new_environment();
+            writeln!(output, "Environment cleared")?;
+            return Ok(CommandOutcome::Reset);
+        }
+        ":env" => {
+            let mut bindings = env.bindings();
+            bindings.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (name, value) in bindings {
+                writeln!(output, "{} = {}", name, value)?;
+            }
+        }
+        _ => match command.strip_prefix(":load") {
+            Some(path) if path.starts_with(char::is_whitespace) => {
+                load_file(path.trim(), env, sources, output)?
+            }
+            _ => writeln!(output, "unknown command: {}", command)?,
+        },
+    }
+
+    Ok(CommandOutcome::Continue)
+}
+
+/// Runs the file at `path` against the session environment, so its bindings
+/// stay available afterwards. Errors are reported without ending the session.
+fn load_file<'src>(
+    path: &str,
+    env: &mut Environment<'src>,
+    sources: &'src Sources,
+    output: &mut dyn Write,
+) -> io::Result<()> {
+    match std::fs::read_to_string(path) {
+        Ok(source) => eval_source(sources.alloc(source), env, output, false),
+        Err(err) => writeln!(output, "could not read {}: {}", path, err),
+    }
+}
+
+/// Parses and evaluates `source`, printing any errors, and the result when
+/// `print_value` is set.
+fn eval_source<'src>(
+    source: &'src str,
+    env: &mut Environment<'src>,
+    output: &mut dyn Write,
+    print_value: bool,
+) -> io::Result<()> {
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    if let Some(mut program) = parser.parse_program() {
+        if parser.errors.is_empty() {
+            match eval_program_with_output(&mut program, env, output) {
+                Ok(evaluated) => {
+                    if print_value {
+                        writeln!(output, "{}", evaluated)?
+                    }
+                }
+                Err(message) => writeln!(output, "ERROR: {}", message)?,
+            }
+        } else {
+            write!(output, "{}", format_errors(&parser.errors, source))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A session environment whose enclosing scope holds the prelude, so `:env`
+/// only lists the user's own bindings.
+pub fn new_environment<'a>() -> Environment<'a> {
+    let mut prelude = Environment::new();
+    with_prelude(&mut prelude);
+    Environment::with_outer(prelude)
+}
+
+/// Whether `source` stops partway through a construct, so the REPL should
+/// keep reading lines: a bracket is still open, or a string or block comment
+/// is unterminated.
+pub fn is_incomplete(source: &str) -> bool {
+    let mut depth = 0;
+
+    for token in Lexer::new(source).tokens() {
+        match token.token_type {
+            TokenType::LParen | TokenType::LBrace | TokenType::LBracket => depth += 1,
+            TokenType::RParen | TokenType::RBrace | TokenType::RBracket => depth -= 1,
+            TokenType::Illegal => return token.literal.starts_with("unterminated"),
+            _ => {}
+        }
+    }
+
+    depth > 0
+}
+
+pub fn start(mut input: impl BufRead, output: &mut dyn Write) -> io::Result<()> {
+    // Each reset starts over with new sources, so the inputs of a long session
+    // are dropped along with the environment that borrowed from them.
+    loop {
+        let sources = Sources::new();
+        let mut env = new_environment();
+
+        let outcome = read_eval_loop(&mut input, output, &mut env, &sources);
+        env.clear();
+        if outcome? != CommandOutcome::Reset {
+            return Ok(());
+        }
+    }
+}
+
+/// Reads and evaluates inputs until the session ends, returning `Quit`, or
+/// is reset, returning `Reset`.
+fn read_eval_loop<'src>(
+    input: &mut impl BufRead,
+    output: &mut dyn Write,
+    env: &mut Environment<'src>,
+    sources: &'src Sources,
+) -> io::Result<CommandOutcome> {
+    let mut source = String::new();
+
+    loop {
+        let prompt = if source.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        };
+        write!(output, "{}", prompt)?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(output, "Exiting Repl")?;
+            return Ok(CommandOutcome::Quit);
+        }
+
+        if source.is_empty() {
+            if line.trim_end().is_empty() {
+                writeln!(output, "Exiting Repl")?;
+                return Ok(CommandOutcome::Quit);
+            }
+
+            let trimmed = line.trim();
+            if trimmed.starts_with(':') {
+                match run_command(trimmed, env, sources, output)? {
+                    CommandOutcome::Continue => continue,
+                    CommandOutcome::Reset => return Ok(CommandOutcome::Reset),
+                    CommandOutcome::Quit => {
+                        writeln!(output, "Exiting Repl")?;
+                        return Ok(CommandOutcome::Quit);
+                    }
+                }
+            }
+        }
+
+        // A blank continuation line gives up on waiting and evaluates what has
+        // been entered so far, reporting whatever is still missing.
+        let blank = line.trim_end().is_empty();
+        source.push_str(&line);
+        if !blank && is_incomplete(&source) {
+            continue;
+        }
+
+        let source = sources.alloc(std::mem::take(&mut source));
+        eval_source(source, env, output, true)?;
+    }
+}
@@ -0,0 +1,213 @@
+use super::{CommandOutcome, Sources, is_incomplete, run_command, start};
+use crate::{Environment, eval::object::Object};
+
+#[test]
+fn test_repl_keeps_bindings_between_lines() {
+    let input = "let x = 5;\nx + 1;\n";
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> nil\n> 6\n> Exiting Repl\n"
+    );
+}
+
+#[test]
+fn test_repl_keeps_functions_between_lines() {
+    let input = "let add = fn(a, b) { a + b };\nlet x = add(1, 2);\nadd(x, 3)\n";
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> nil\n> nil\n> 6\n> Exiting Repl\n"
+    );
+}
+
+#[test]
+fn test_run_command_quit() {
+    let sources = Sources::new();
+    let mut env = Environment::new();
+    let mut output = Vec::new();
+
+    let outcome = run_command(":quit", &mut env, &sources, &mut output).unwrap();
+
+    assert_eq!(outcome, CommandOutcome::Quit);
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_run_command_reset() {
+    let sources = Sources::new();
+    let mut env = Environment::new();
+    env.set("x", Object::Int(5));
+    let mut output = Vec::new();
+
+    let outcome = run_command(":reset", &mut env, &sources, &mut output).unwrap();
+
+    assert_eq!(outcome, CommandOutcome::Reset);
+    assert!(env.get("x").is_none());
+    assert_eq!(String::from_utf8(output).unwrap(), "Environment cleared\n");
+}
+
+#[test]
+fn test_run_command_env() {
+    let sources = Sources::new();
+    let mut env = Environment::new();
+    env.set("y", Object::Str("hello".into()));
+    env.set("x", Object::Int(5));
+    let mut output = Vec::new();
+
+    let outcome = run_command(":env", &mut env, &sources, &mut output).unwrap();
+
+    assert_eq!(outcome, CommandOutcome::Continue);
+    assert_eq!(String::from_utf8(output).unwrap(), "x = 5\ny = hello\n");
+}
+
+#[test]
+fn test_run_command_unknown() {
+    let sources = Sources::new();
+    let mut env = Environment::new();
+    let mut output = Vec::new();
+
+    let outcome = run_command(":nope", &mut env, &sources, &mut output).unwrap();
+
+    assert_eq!(outcome, CommandOutcome::Continue);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "unknown command: :nope\n"
+    );
+}
+
+#[test]
+fn test_repl_meta_commands() {
+    let input = "let x = 5;\n:env\n:reset\nx\n:quit\nunreachable\n";
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> nil\n> x = 5\n> Environment cleared\n> ERROR: identifier not found: x\n> Exiting Repl\n"
+    );
+}
+
+#[test]
+fn test_repl_has_prelude() {
+    let input =
+        "map([1, 2], fn(x) { x + 1 })\n:env\n:reset\nreduce([1, 2, 3], 0, fn(a, b) { a + b })\n";
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> [2, 3]\n> > Environment cleared\n> 6\n> Exiting Repl\n"
+    );
+}
+
+#[test]
+fn test_is_incomplete() {
+    let tests = [
+        ("fn(x) {", true),
+        ("let add = fn(x, y) {\n", true),
+        ("add(1,", true),
+        ("[1, 2", true),
+        ("\"unterminated", true),
+        ("/* open comment", true),
+        ("5 + 5", false),
+        ("let add = fn(x, y) {\n    x + y\n};\n", false),
+        ("fn(x) { x }(", true),
+        ("}", false),
+        ("let x 5;", false),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(is_incomplete(input), expected, "input: {:?}", input);
+    }
+}
+
+#[test]
+fn test_repl_accumulates_multiline_input() {
+    let input = "let add = fn(x, y) {\nx + y\n};\nadd(1,\n2)\nlet y = (1 +\n\n";
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> ... ... nil
+> ... 3
+> ... Woops! We ran into some monkey business here!
+ parser errors:
+error: no prefix parse function for Eof found
+ --> 3:1
+  |
+3 | 
+  | ^
+error: expected next token to be RParen, got Eof instead
+ --> 3:1
+  |
+3 | 
+  | ^
+> Exiting Repl
+"
+    );
+}
+
+#[test]
+fn test_load_file_into_session() {
+    let path = std::env::temp_dir().join(format!("repl_load-{}.monkey", std::process::id()));
+    std::fs::write(&path, "let answer = 42;\nlet double = fn(x) { x * 2 };\n").unwrap();
+    let input = format!(":load {}\nanswer\ndouble(answer)\n", path.display());
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> > 42\n> 84\n> Exiting Repl\n"
+    );
+}
+
+#[test]
+fn test_load_file_reports_errors() {
+    let path = std::env::temp_dir().join(format!("repl_load_error-{}.monkey", std::process::id()));
+    std::fs::write(&path, "let x = 1;\nx + true;\n").unwrap();
+    let input = format!(":load {}\nx\n", path.display());
+    let mut output = Vec::new();
+
+    start(input.as_bytes(), &mut output).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> ERROR: type mismatch: INTEGER + BOOLEAN\n> 1\n> Exiting Repl\n"
+    );
+}
+
+#[test]
+fn test_load_missing_file() {
+    let sources = Sources::new();
+    let mut env = Environment::new();
+    let mut output = Vec::new();
+
+    let outcome = run_command(
+        ":load /no/such/file.monkey",
+        &mut env,
+        &sources,
+        &mut output,
+    )
+    .unwrap();
+
+    assert_eq!(outcome, CommandOutcome::Continue);
+    assert!(
+        String::from_utf8(output)
+            .unwrap()
+            .starts_with("could not read /no/such/file.monkey: ")
+    );
+}
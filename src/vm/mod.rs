@@ -0,0 +1,5 @@
+#[cfg(test)]
+mod tests;
+
+pub mod vm;
+pub use vm::Vm;
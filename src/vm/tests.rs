@@ -0,0 +1,56 @@
+use crate::{
+    Environment, Lexer, Parser, compiler::Compiler, eval::eval::eval_program, eval::object::Object,
+    vm::Vm,
+};
+
+fn run_vm(input: &str) -> Object<'_> {
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser
+        .parse_program()
+        .expect("parse_program() returned None");
+    assert!(parser.errors.is_empty());
+
+    let mut compiler = Compiler::new();
+    compiler.compile(&program).expect("compilation failed");
+
+    Vm::new(compiler.bytecode()).run()
+}
+
+#[test]
+fn test_integer_arithmetic_matches_eval() {
+    let inputs = [
+        "5",
+        "10",
+        "-5",
+        "-10",
+        "5 + 5 + 5 + 5 - 10",
+        "2 * 2 * 2 * 2 * 2",
+        " - 50 + 100 + -50",
+        "5 * 2 + 10",
+        "5 + 2 * 10",
+        "20 + 2 * -10",
+        "50 / 2 * 2 + 10",
+        "2 * (5 + 10)",
+        "3 * 3 * 3 + 10",
+        "3 * (3 * 3) + 10",
+        "(5 + 10 * 2 + 15 / 3) * 2 + -10",
+        "1 / 0",
+        "1 / 0; 5;",
+        "9223372036854775807 + 1",
+        "-9223372036854775807 - 2",
+        "9223372036854775807 * 2",
+    ];
+
+    for input in inputs {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let mut program = parser
+            .parse_program()
+            .expect("parse_program() returned None");
+        let expected =
+            eval_program(&mut program, &mut Environment::new()).unwrap_or_else(Object::Error);
+
+        assert_eq!(run_vm(input), expected, "input: {}", input);
+    }
+}
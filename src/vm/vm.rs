@@ -0,0 +1,98 @@
+use crate::{
+    compiler::{Bytecode, Instruction},
+    eval::object::Object,
+};
+
+const STACK_SIZE: usize = 2048;
+
+#[derive(Debug)]
+pub struct Vm<'a> {
+    instructions: Vec<Instruction>,
+    constants: Vec<Object<'a>>,
+    stack: Vec<Object<'a>>,
+    last_popped: Object<'a>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(bytecode: Bytecode<'a>) -> Self {
+        Vm {
+            instructions: bytecode.instructions,
+            constants: bytecode.constants,
+            stack: Vec::with_capacity(STACK_SIZE),
+            last_popped: Object::Null,
+        }
+    }
+
+    /// Executes the bytecode and returns the value of the last expression
+    /// statement, or an `Object::Error` if execution failed.
+    pub fn run(&mut self) -> Object<'a> {
+        for ip in 0..self.instructions.len() {
+            let result = match self.instructions[ip] {
+                Instruction::OpConstant(index) => self.push(self.constants[index].clone()),
+                Instruction::OpAdd
+                | Instruction::OpSub
+                | Instruction::OpMul
+                | Instruction::OpDiv => self.execute_binary_operation(self.instructions[ip]),
+                Instruction::OpMinus => match self.pop() {
+                    Object::Int(value) => match value.checked_neg() {
+                        Some(value) => self.push(Object::Int(value)),
+                        None => Err("integer overflow".to_string()),
+                    },
+                    other => Err(format!("unknown operator: -{}", other.type_name())),
+                },
+                Instruction::OpPop => {
+                    self.last_popped = self.pop();
+                    Ok(())
+                }
+            };
+
+            if let Err(message) = result {
+                return Object::Error(message);
+            }
+        }
+
+        self.last_popped.clone()
+    }
+
+    fn execute_binary_operation(&mut self, instruction: Instruction) -> Result<(), String> {
+        let right = self.pop();
+        let left = self.pop();
+
+        let (l, r) = match (&left, &right) {
+            (Object::Int(l), Object::Int(r)) => (*l, *r),
+            _ => {
+                return Err(format!(
+                    "unsupported types for binary operation: {} {}",
+                    left.type_name(),
+                    right.type_name()
+                ));
+            }
+        };
+
+        let result = match instruction {
+            Instruction::OpAdd => l.checked_add(r),
+            Instruction::OpSub => l.checked_sub(r),
+            Instruction::OpMul => l.checked_mul(r),
+            Instruction::OpDiv if r == 0 => return Err("division by zero".to_string()),
+            Instruction::OpDiv => l.checked_div(r),
+            _ => unreachable!("not a binary operation: {:?}", instruction),
+        };
+
+        match result {
+            Some(value) => self.push(Object::Int(value)),
+            None => Err("integer overflow".to_string()),
+        }
+    }
+
+    fn push(&mut self, object: Object<'a>) -> Result<(), String> {
+        if self.stack.len() >= STACK_SIZE {
+            return Err("stack overflow".to_string());
+        }
+        self.stack.push(object);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Object<'a> {
+        self.stack.pop().unwrap_or(Object::Null)
+    }
+}
@@ -0,0 +1,134 @@
+use std::{path::PathBuf, process::Command};
+
+fn write_script(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}.monkey", name, std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+fn monkey() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_interpreter-book"))
+}
+
+#[test]
+fn test_run_file() {
+    let path = write_script("run_file", "let add = fn(a, b) { a + b };\nadd(40, 2);\n");
+
+    let output = monkey().arg("run").arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "42\n");
+}
+
+#[test]
+fn test_run_file_without_subcommand() {
+    let path = write_script("run_path", "puts(\"hi\"); 1 + 1");
+
+    let output = monkey().arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "hi\n2\n");
+}
+
+#[test]
+fn test_run_file_with_parse_errors() {
+    let path = write_script("run_parse_error", "let x 5;");
+
+    let output = monkey().arg("run").arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("expected next token to be Assign, got Int instead")
+    );
+}
+
+#[test]
+fn test_no_arguments_prints_usage() {
+    let output = monkey().output().unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("usage:")
+    );
+}
+
+#[test]
+fn test_tokens_dumps_token_stream() {
+    let path = write_script("tokens", "add(1, x)");
+
+    for args in [&["tokens"][..], &["run", "--tokens"][..]] {
+        let output = monkey().args(args).arg(&path).output().unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "Ident \"add\" 0..3
+LParen \"(\" 3..4
+Int \"1\" 4..5
+Comma \",\" 5..6
+Ident \"x\" 7..8
+RParen \")\" 8..9
+"
+        );
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_ast_dumps_parsed_program() {
+    let path = write_script("ast", "let x = 2 + 3;\nputs(x * 2)");
+
+    for args in [&["ast"][..], &["run", "--ast"][..]] {
+        let output = monkey().args(args).arg(&path).output().unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "let x = (2 + 3);\nputs((x * 2))\n"
+        );
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_run_file_with_runtime_error() {
+    let path = write_script("run_runtime_error", "puts(1); 5 + true; puts(2);");
+
+    let output = monkey().arg("run").arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "ERROR: type mismatch: INTEGER + BOOLEAN\n"
+    );
+}
+
+#[test]
+fn test_run_file_with_timing() {
+    let path = write_script("run_time", "puts(1 + 1)");
+
+    let output = monkey()
+        .args(["run", "--time"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\nnil\n");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines: Vec<_> = stderr.lines().collect();
+    assert_eq!(lines.len(), 2, "stderr: {}", stderr);
+    assert!(lines[0].starts_with("parse: "));
+    assert!(lines[1].starts_with("eval: "));
+}